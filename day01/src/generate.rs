@@ -0,0 +1,118 @@
+use crate::{Dial, Direction, Instruction};
+use aoc::rng::Rng;
+
+// Instructions that take `dial` to exactly `part1` rotations ending on zero
+// and `part2` clicks onto zero, or None if no list does. Passes that must not
+// land come first, as few long rotations ending next to zero as the step
+// width allows, then one rotation per landing.
+pub fn synthesize(dial: Dial, part1: u64, part2: u64) -> Option<Vec<Instruction>> {
+    // Every landing is also a pass
+    if part2 < part1 {
+        return None;
+    }
+    let right = |steps| Instruction {
+        direction: Direction::Right,
+        steps,
+    };
+    let n = dial.size;
+
+    // On a one-position dial every click lands, so every rotation counts for
+    // part 1 and its steps for part 2
+    if n == 1 {
+        if part1 == 0 {
+            return (part2 == 0).then(Vec::new);
+        }
+        let mut instructions = vec![right(part2 - part1 + 1)];
+        instructions.extend(std::iter::repeat_n(right(1), part1 as usize - 1));
+        return Some(instructions);
+    }
+
+    let mut instructions = Vec::new();
+    let mut position = dial.position;
+    let to_zero = |position: u64| if position == 0 { n } else { n - position };
+
+    // k passes ending on 1 take at most k * n + 1 steps
+    let most = (u64::MAX - 1) / n;
+    let mut extra = part2 - part1;
+    while extra > 0 {
+        let k = extra.min(most);
+        instructions.push(right(to_zero(position) + (k - 1) * n + 1));
+        position = 1;
+        extra -= k;
+    }
+
+    for _ in 0..part1 {
+        instructions.push(right(to_zero(position)));
+        position = 0;
+    }
+    Some(instructions)
+}
+
+// `count` rotations of 1..=max_steps in either direction, like puzzle inputs
+pub fn random_instructions(rng: &mut Rng, count: usize, max_steps: u64) -> Vec<Instruction> {
+    (0..count)
+        .map(|_| Instruction {
+            direction: if rng.chance(1, 2) {
+                Direction::Left
+            } else {
+                Direction::Right
+            },
+            steps: rng.between(1, max_steps),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{part1, part2};
+    use std::io::Cursor;
+
+    fn counts(dial: Dial, instructions: &[Instruction]) -> (u64, u64) {
+        let text: String = instructions.iter().map(|i| format!("{i}\n")).collect();
+        (
+            part1(Cursor::new(&text), dial),
+            part2(Cursor::new(&text), dial),
+        )
+    }
+
+    #[test]
+    fn test_synthesize() {
+        for size in 1..6 {
+            for start in 0..size {
+                let dial = Dial::new(size, start);
+                for want1 in 0..5 {
+                    for want2 in want1..want1 + 12 {
+                        let feasible = size > 1 || want1 > 0 || want2 == 0;
+                        match synthesize(dial, want1, want2) {
+                            Some(instructions) => {
+                                assert_eq!(counts(dial, &instructions), (want1, want2))
+                            }
+                            None => assert!(!feasible, "{size} {start} {want1} {want2}"),
+                        }
+                    }
+                }
+                assert_eq!(synthesize(dial, 3, 2), None);
+            }
+        }
+
+        // Too many passes for a single rotation of u64 steps
+        let dial = Dial::new(u64::MAX / 2, 7);
+        let instructions = synthesize(dial, 1, 6).unwrap();
+        assert_eq!(instructions.len(), 4);
+        assert_eq!(counts(dial, &instructions), (1, 6));
+    }
+
+    #[test]
+    fn test_random_round_trip() {
+        let mut rng = Rng::new(43);
+        for _ in 0..200 {
+            let size = rng.between(1, 200);
+            let dial = Dial::new(size, rng.below(size));
+            let instructions = random_instructions(&mut rng, 50, 10 * size);
+            let (want1, want2) = counts(dial, &instructions);
+            let synthesized = synthesize(dial, want1, want2).unwrap();
+            assert_eq!(counts(dial, &synthesized), (want1, want2));
+        }
+    }
+}
@@ -1,16 +1,36 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+mod generate;
 
-#[derive(Debug, Clone, Copy)]
+use aoc::args::Args;
+use aoc::input::{Input, read_input};
+use aoc::rng::Rng;
+use std::fmt;
+use std::io::BufRead;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Direction {
     Left,
     Right,
 }
 
-#[derive(Debug, Clone, Copy)]
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Direction::Left => write!(f, "L"),
+            Direction::Right => write!(f, "R"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 struct Instruction {
     direction: Direction,
-    steps: u16,
+    steps: u64,
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.direction, self.steps)
+    }
 }
 
 fn parse_line(line: &str) -> Instruction {
@@ -19,83 +39,192 @@ fn parse_line(line: &str) -> Instruction {
         b'R' => Direction::Right,
         _ => panic!("Invalid direction"),
     };
-    let steps: u16 = line[1..].parse().expect("Invalid number");
+    let steps: u64 = line[1..].parse().expect("Invalid number");
     Instruction { direction, steps }
 }
 
-fn part1<R: BufRead>(reader: R) -> u32 {
-    let mut position = 50i32;
-    let mut secret: u32 = 0;
+// Times one rotation points the dial at zero
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ZeroHits {
+    // The rotation ends on zero
+    landed: bool,
+    // Clicks onto zero along the way, the last one included
+    passes: u64,
+}
 
-    for line in reader.lines() {
-        let line = line.expect("Failed to read line");
-        if line.is_empty() {
-            continue;
-        }
-        let inst = parse_line(&line);
+// A dial numbered 0..size, pointing at `position`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Dial {
+    size: u64,
+    position: u64,
+}
+
+impl Default for Dial {
+    fn default() -> Self {
+        Dial::new(100, 50)
+    }
+}
 
-        let delta = match inst.direction {
-            Direction::Right => inst.steps as i32,
-            Direction::Left => -(inst.steps as i32),
+impl Dial {
+    fn new(size: u64, position: u64) -> Self {
+        assert!(size > 0, "Dial needs at least one position");
+        assert!(position < size, "Start must be on the dial");
+        Dial { size, position }
+    }
+
+    fn rotate(&mut self, inst: Instruction) -> ZeroHits {
+        let (size, position, steps) =
+            (self.size as u128, self.position as u128, inst.steps as u128);
+
+        // Clicks k in 1..=steps that end on zero
+        let (end, passes) = match inst.direction {
+            Direction::Right => ((position + steps) % size, (position + steps) / size),
+            Direction::Left => {
+                let end = (position + size - steps % size) % size;
+                // Zero is first reached after `position` clicks, or a full
+                // turn when starting on it
+                let first = if position == 0 { size } else { position };
+                let passes = if steps >= first {
+                    (steps - first) / size + 1
+                } else {
+                    0
+                };
+                (end, passes)
+            }
         };
-        position = (position + delta).rem_euclid(100);
 
-        if position == 0 {
-            secret += 1;
+        self.position = end as u64;
+        ZeroHits {
+            landed: end == 0,
+            passes: passes as u64,
         }
     }
-
-    secret
 }
 
-fn part2<R: BufRead>(reader: R) -> u32 {
-    let mut position = 50i32;
-    let mut secret: u32 = 0;
+// One instruction applied to the dial
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Rotation {
+    // 1-based input line of the instruction
+    line: usize,
+    start: u64,
+    instruction: Instruction,
+    end: u64,
+    hits: ZeroHits,
+}
 
-    for line in reader.lines() {
-        let line = line.expect("Failed to read line");
+// Run the instructions on the dial one at a time, skipping blank lines
+fn rotations<R: BufRead>(reader: R, mut dial: Dial) -> impl Iterator<Item = Rotation> {
+    let input = Input::from_reader(reader);
+    let lines: Vec<String> = input.lines().map(String::from).collect();
+    lines.into_iter().enumerate().filter_map(move |(i, line)| {
         if line.is_empty() {
-            continue;
+            return None;
         }
-        let inst = parse_line(&line);
-
-        let steps = inst.steps as i32;
+        let instruction = parse_line(&line);
+        let start = dial.position;
+        let hits = dial.rotate(instruction);
+        Some(Rotation {
+            line: i + 1,
+            start,
+            instruction,
+            end: dial.position,
+            hits,
+        })
+    })
+}
 
-        // Count complete laps
-        secret += (steps / 100) as u32;
+// Add up what `policy` makes of the zero hits of each rotation
+fn count_zeros<I, F>(rotations: I, policy: F) -> u64
+where
+    I: Iterator<Item = Rotation>,
+    F: Fn(ZeroHits) -> u64,
+{
+    rotations.map(|r| policy(r.hits)).sum()
+}
 
-        // Calculate new position after partial rotation
-        let delta = match inst.direction {
-            Direction::Right => steps % 100,
-            Direction::Left => -(steps % 100),
-        };
-        let new_pos = (position + delta).rem_euclid(100);
+// Rotations that end on zero
+fn landed(hits: ZeroHits) -> u64 {
+    hits.landed as u64
+}
 
-        // Count if we pass through 0 (wrap or land on 0, but not if starting at 0)
-        if position != 0 {
-            let wrapped = match inst.direction {
-                Direction::Right => new_pos < position,
-                Direction::Left => new_pos > position,
-            };
-            if wrapped || new_pos == 0 {
-                secret += 1;
-            }
-        }
+// Every click onto zero
+fn passed(hits: ZeroHits) -> u64 {
+    hits.passes
+}
 
-        position = new_pos;
-    }
+fn part1<R: BufRead>(reader: R, dial: Dial) -> u64 {
+    count_zeros(rotations(reader, dial), landed)
+}
 
-    secret
+fn part2<R: BufRead>(reader: R, dial: Dial) -> u64 {
+    count_zeros(rotations(reader, dial), passed)
 }
 
-fn read_input() -> BufReader<File> {
-    let file = File::open("inputs/day01.txt").expect("Failed to open input file");
-    BufReader::new(file)
+// CSV with one row per rotation, and the zeros each part counts for it
+fn trace_csv<I: Iterator<Item = Rotation>>(rotations: I) -> String {
+    let mut out = String::from("line,start,direction,steps,end,part1,part2\n");
+    for r in rotations {
+        out += &format!(
+            "{},{},{},{},{},{},{}\n",
+            r.line,
+            r.start,
+            r.instruction.direction,
+            r.instruction.steps,
+            r.end,
+            landed(r.hits),
+            passed(r.hits)
+        );
+    }
+    out
 }
 
 fn main() {
-    let result1 = part1(read_input());
-    let result2 = part2(read_input());
+    let args = Args::from_env();
+    let size = args.number("--size").unwrap_or(100);
+    let dial = Dial::new(size, args.number("--start").unwrap_or(size / 2));
+
+    // Print an input instead: one with the zero counts of `--part1` and
+    // `--part2`, or a random one from `--seed`
+    if args.is_command("generate") {
+        let instructions = match (args.number("--part1"), args.number("--part2")) {
+            (Some(want1), Some(want2)) => {
+                generate::synthesize(dial, want1, want2).unwrap_or_else(|| {
+                    eprintln!("No instructions give {want1} landings and {want2} passes");
+                    std::process::exit(1);
+                })
+            }
+            (None, None) => {
+                let seed = args.number("--seed");
+                let mut rng = Rng::new(seed.expect("Need --seed or --part1/--part2"));
+                let count = args.number::<usize>("--count").unwrap_or(4000);
+                let max_steps = args
+                    .number("--max-steps")
+                    .unwrap_or(size.saturating_mul(10));
+                generate::random_instructions(&mut rng, count, max_steps)
+            }
+            _ => panic!("--part1 and --part2 go together"),
+        };
+        for instruction in instructions {
+            println!("{instruction}");
+        }
+        return;
+    }
+
+    // Replay only the instructions up to and including line `--until`
+    let until = args.number("--until").unwrap_or(usize::MAX);
+    let replay = || rotations(read_input(1), dial).take_while(move |r| r.line <= until);
+
+    if args.has("--trace") {
+        print!("{}", trace_csv(replay()));
+        return;
+    }
+    let (result1, result2) = if until == usize::MAX {
+        (part1(read_input(1), dial), part2(read_input(1), dial))
+    } else {
+        let position = replay().last().map_or(dial.position, |r| r.end);
+        println!("Dial at {position} after line {until}");
+        (count_zeros(replay(), landed), count_zeros(replay(), passed))
+    };
     println!("Part 1: {result1}");
     println!("Part 2: {result2}");
 }
@@ -103,20 +232,145 @@ fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc::diff::{self, Part};
+    use std::io::Cursor;
+
+    const EXAMPLE: &str = "L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82\n";
+
+    #[test]
+    fn test_example() {
+        assert_eq!(part1(Cursor::new(EXAMPLE), Dial::default()), 3);
+        assert_eq!(part2(Cursor::new(EXAMPLE), Dial::default()), 6);
+
+        // Saved on Windows, with a byte order mark and a trailing blank line
+        let saved = format!("\u{feff}{}\r\n\r\n", EXAMPLE.replace('\n', " \r\n"));
+        assert_eq!(part1(Cursor::new(&saved), Dial::default()), 3);
+        assert_eq!(part2(Cursor::new(&saved), Dial::default()), 6);
+    }
+
+    // Turn the dial one click at a time
+    fn simulate(dial: &mut Dial, inst: Instruction) -> ZeroHits {
+        let mut passes = 0;
+        for _ in 0..inst.steps {
+            dial.position = match inst.direction {
+                Direction::Right => (dial.position + 1) % dial.size,
+                Direction::Left => (dial.position + dial.size - 1) % dial.size,
+            };
+            passes += (dial.position == 0) as u64;
+        }
+        ZeroHits {
+            landed: dial.position == 0,
+            passes,
+        }
+    }
+
+    #[test]
+    fn test_matches_simulation() {
+        let mut rng = Rng::new(2025);
+        for _ in 0..2000 {
+            let size = rng.between(1, 20);
+            let start = rng.below(size);
+            let (mut fast, mut slow) = (Dial::new(size, start), Dial::new(size, start));
+
+            for _ in 0..20 {
+                let inst = Instruction {
+                    direction: if rng.chance(1, 2) {
+                        Direction::Left
+                    } else {
+                        Direction::Right
+                    },
+                    steps: rng.below(4 * size),
+                };
+                assert_eq!(fast.rotate(inst), simulate(&mut slow, inst), "{inst:?}");
+                assert_eq!(fast, slow);
+            }
+        }
+    }
+
+    #[test]
+    fn test_trace() {
+        let input = "L68\n\nL30\nR48\nL5";
+        let trace = trace_csv(rotations(Cursor::new(input), Dial::default()));
+        assert_eq!(
+            trace,
+            "line,start,direction,steps,end,part1,part2\n\
+             1,50,L,68,82,0,1\n\
+             3,82,L,30,52,0,0\n\
+             4,52,R,48,0,1,1\n\
+             5,0,L,5,95,0,0\n"
+        );
+
+        let replay = rotations(Cursor::new(EXAMPLE), Dial::default()).take_while(|r| r.line <= 6);
+        assert_eq!(count_zeros(replay, passed), 4);
+    }
+
+    // Reference answers by turning the default dial one click at a time
+    fn simulate_parts(input: &str) -> (u64, u64) {
+        let mut dial = Dial::default();
+        let (mut landed, mut passes) = (0, 0);
+        for line in input.lines().filter(|l| !l.is_empty()) {
+            let hits = simulate(&mut dial, parse_line(line));
+            landed += hits.landed as u64;
+            passes += hits.passes;
+        }
+        (landed, passes)
+    }
+
+    #[test]
+    fn test_differential() {
+        let parts = [
+            Part::new(
+                "day01 part 1",
+                |input: &str| simulate_parts(input).0,
+                |input: &str| part1(Cursor::new(input), Dial::default()),
+            ),
+            Part::new(
+                "day01 part 2",
+                |input: &str| simulate_parts(input).1,
+                |input: &str| part2(Cursor::new(input), Dial::default()),
+            ),
+        ];
+        let inputs = (0..50).map(|seed| {
+            let instructions = generate::random_instructions(&mut Rng::new(seed), 200, 1000);
+            instructions.iter().map(|i| format!("{i}\n")).collect()
+        });
+        diff::check(&parts, inputs).unwrap_or_else(|m| panic!("{m}"));
+    }
+
+    #[test]
+    fn test_huge_steps() {
+        let mut dial = Dial::new(u64::MAX, u64::MAX - 1);
+        let right = dial.rotate(parse_line(&format!("R{}", u64::MAX)));
+        assert_eq!(dial.position, u64::MAX - 1);
+        assert_eq!(
+            right,
+            ZeroHits {
+                landed: false,
+                passes: 1
+            }
+        );
+
+        let mut dial = Dial::new(3, 0);
+        let left = dial.rotate(parse_line(&format!("L{}", u64::MAX)));
+        assert_eq!(dial.position, 0);
+        assert_eq!(
+            left,
+            ZeroHits {
+                landed: true,
+                passes: u64::MAX / 3
+            }
+        );
+    }
 
     #[test]
     fn test_part1() {
-        let file = File::open("../inputs/day01.txt").expect("Failed to read input file");
-        let reader = BufReader::new(file);
-        let result = part1(reader);
+        let result = part1(read_input(1), Dial::default());
         assert_eq!(result, 992);
     }
 
     #[test]
     fn test_part2() {
-        let file = File::open("../inputs/day01.txt").expect("Failed to read input file");
-        let reader = BufReader::new(file);
-        let result = part2(reader);
+        let result = part2(read_input(1), Dial::default());
         assert_eq!(result, 6133);
     }
 }
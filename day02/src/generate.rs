@@ -0,0 +1,35 @@
+use aoc::rng::Rng;
+
+// One line of `count` comma-separated ID ranges. Each starts at a random ID
+// of up to `max_digits` digits and covers at most `width` IDs.
+pub fn id_ranges(rng: &mut Rng, count: usize, max_digits: u32, width: u64) -> String {
+    assert!((1..=18).contains(&max_digits), "IDs have 1 to 18 digits");
+    let ranges: Vec<_> = (0..count)
+        .map(|_| {
+            let digits = rng.between(1, max_digits as u64) as u32;
+            let start = rng.between(10u64.pow(digits - 1), 10u64.pow(digits) - 1);
+            let end = start + rng.below(width.max(1));
+            format!("{start}-{end}")
+        })
+        .collect();
+    ranges.join(",") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{is_double_pattern_string, is_repeating_pattern_kmp, process_ranges};
+    use std::io::Cursor;
+
+    #[test]
+    fn test_id_ranges() {
+        let text = id_ranges(&mut Rng::new(2), 25, 6, 5000);
+        assert_eq!(text.lines().count(), 1);
+        assert_eq!(text.trim_end().split(',').count(), 25);
+
+        // Every double is also a repetition
+        let doubles = process_ranges(Cursor::new(&text), is_double_pattern_string);
+        let repeats = process_ranges(Cursor::new(&text), is_repeating_pattern_kmp);
+        assert!(doubles > 0 && doubles <= repeats);
+    }
+}
@@ -1,5 +1,9 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+mod generate;
+
+use aoc::args::Args;
+use aoc::input::{Input, read_input};
+use aoc::rng::Rng;
+use std::io::BufRead;
 
 fn is_double_pattern_string(x: u64) -> bool {
     let s = x.to_string();
@@ -43,12 +47,7 @@ where
 {
     let mut sum: u64 = 0;
 
-    for line in reader.lines() {
-        let line = line.expect("Failed to read line");
-        if line.is_empty() {
-            continue;
-        }
-
+    for line in Input::from_reader(reader).non_empty_lines() {
         for range_str in line.split(',') {
             if let Some((start_str, end_str)) = range_str.split_once('-') {
                 let start: u64 = start_str.parse().expect("Invalid start number");
@@ -66,22 +65,33 @@ where
     sum
 }
 
-fn read_input() -> BufReader<File> {
-    let file = File::open("inputs/day02.txt").expect("Failed to open input file");
-    BufReader::new(file)
-}
-
 fn main() {
-    let result1 = process_ranges(read_input(), is_double_pattern_string);
+    let args = Args::from_env();
+
+    if args.is_command("generate") {
+        let mut rng = Rng::new(args.number("--seed").expect("Missing --seed"));
+        let count = args.number::<usize>("--count").unwrap_or(40);
+        let max_digits = args.number::<u32>("--digits").unwrap_or(10);
+        let width = args.number("--width").unwrap_or(100_000);
+        print!(
+            "{}",
+            generate::id_ranges(&mut rng, count, max_digits, width)
+        );
+        return;
+    }
+
+    let result1 = process_ranges(read_input(2), is_double_pattern_string);
     println!("Part 1: {result1}");
 
-    let result2 = process_ranges(read_input(), is_repeating_pattern_kmp);
+    let result2 = process_ranges(read_input(2), is_repeating_pattern_kmp);
     println!("Part 2: {result2}");
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc::diff::{self, Part};
+    use std::io::Cursor;
 
     #[test]
     fn test_is_double_pattern_string() {
@@ -103,18 +113,50 @@ mod tests {
         assert!(!is_repeating_pattern_kmp(1234));
     }
 
+    // Reference for part 1: the first half written twice
+    fn is_double_naive(n: u64) -> bool {
+        let s = n.to_string();
+        s.len().is_multiple_of(2) && s[..s.len() / 2].repeat(2) == s
+    }
+
+    // Reference for part 2: try every period that divides the length
+    fn is_repeating_naive(n: u64) -> bool {
+        let s = n.to_string();
+        (1..s.len())
+            .filter(|p| s.len().is_multiple_of(*p))
+            .any(|p| s[..p].repeat(s.len() / p) == s)
+    }
+
+    #[test]
+    fn test_differential() {
+        let parts = [
+            Part::new(
+                "day02 part 1",
+                |input: &str| process_ranges(Cursor::new(input), is_double_naive),
+                |input: &str| process_ranges(Cursor::new(input), is_double_pattern_string),
+            ),
+            Part::new(
+                "day02 part 2",
+                |input: &str| process_ranges(Cursor::new(input), is_repeating_naive),
+                |input: &str| process_ranges(Cursor::new(input), is_repeating_pattern_kmp),
+            ),
+        ];
+        // One range per line, so shrinking can drop single ranges
+        let inputs = (0..20)
+            .map(|seed| generate::id_ranges(&mut Rng::new(seed), 10, 8, 2000).replace(',', "\n"));
+        diff::check(&parts, inputs).unwrap_or_else(|m| panic!("{m}"));
+    }
+
     #[test]
     fn test_part1() {
-        let file = File::open("../inputs/day02.txt").expect("Failed to read input file");
-        let reader = BufReader::new(file);
+        let reader = read_input(2);
         let result = process_ranges(reader, is_double_pattern_string);
         assert_eq!(result, 23701357374);
     }
 
     #[test]
     fn test_part2() {
-        let file = File::open("../inputs/day02.txt").expect("Failed to read input file");
-        let reader = BufReader::new(file);
+        let reader = read_input(2);
         let result = process_ranges(reader, is_repeating_pattern_kmp);
         assert_eq!(result, 34284458938);
     }
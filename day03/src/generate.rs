@@ -0,0 +1,32 @@
+use aoc::rng::Rng;
+
+// `count` banks of `length` battery joltages, each a digit from 1 to 9
+pub fn banks(rng: &mut Rng, count: usize, length: usize) -> String {
+    (0..count)
+        .map(|_| {
+            let mut bank: String = (0..length)
+                .map(|_| char::from(b'1' + rng.below(9) as u8))
+                .collect();
+            bank.push('\n');
+            bank
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{find_max_joltage_part1, find_max_joltage_part2};
+
+    #[test]
+    fn test_banks() {
+        let text = banks(&mut Rng::new(3), 50, 40);
+        assert_eq!(text.lines().count(), 50);
+        for bank in text.lines() {
+            assert_eq!(bank.len(), 40);
+            assert!(!bank.contains('0'));
+            // Twelve batteries always beat two
+            assert!(find_max_joltage_part2(bank) > find_max_joltage_part1(bank));
+        }
+    }
+}
@@ -1,5 +1,9 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+mod generate;
+
+use aoc::args::Args;
+use aoc::input::{Input, read_input};
+use aoc::rng::Rng;
+use std::io::BufRead;
 
 fn find_max_joltage_part1(line: &str) -> u64 {
     let bytes = line.as_bytes();
@@ -67,9 +71,9 @@ fn find_max_joltage_part2(digits: &str) -> u64 {
             max_idx = suffix_candidate;
         } else {
             // The suffix max is too far right, scan to find max in [start, end]
-            for i in start..=end {
-                if chars[i] > max_char {
-                    max_char = chars[i];
+            for (i, &ch) in chars.iter().enumerate().take(end + 1).skip(start) {
+                if ch > max_char {
+                    max_char = ch;
                     max_idx = i;
                 }
             }
@@ -88,34 +92,51 @@ where
 {
     let mut total = 0;
 
-    for line in reader.lines() {
-        let line = line.expect("Failed to read line");
-        if line.is_empty() {
-            continue;
-        }
-        total += f(&line);
+    for line in Input::from_reader(reader).non_empty_lines() {
+        total += f(line);
     }
 
     total
 }
 
-fn read_input() -> BufReader<File> {
-    let file = File::open("inputs/day03.txt").expect("Failed to open input file");
-    BufReader::new(file)
-}
-
 fn main() {
-    let result = process_input(read_input(), find_max_joltage_part1);
+    let args = Args::from_env();
+
+    if args.is_command("generate") {
+        let mut rng = Rng::new(args.number("--seed").expect("Missing --seed"));
+        let count = args.number::<usize>("--count").unwrap_or(200);
+        let length = args.number::<usize>("--length").unwrap_or(100);
+        print!("{}", generate::banks(&mut rng, count, length));
+        return;
+    }
+
+    let result = process_input(read_input(3), find_max_joltage_part1);
     println!("Part 1: {result}");
-    let result = process_input(read_input(), find_max_joltage_part2);
+    let result = process_input(read_input(3), find_max_joltage_part2);
     println!("Part 2: {result}");
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc::diff::{self, Part};
     use std::io::Cursor;
 
+    // Reference: the largest number made of `k` of the digits in order,
+    // trying every combination
+    fn max_joltage_combinations(digits: &[u8], k: usize, value: u64) -> u64 {
+        if k == 0 {
+            return value;
+        }
+        (0..=digits.len() - k)
+            .map(|i| {
+                let value = value * 10 + (digits[i] - b'0') as u64;
+                max_joltage_combinations(&digits[i + 1..], k - 1, value)
+            })
+            .max()
+            .unwrap()
+    }
+
     #[test]
     fn test_find_max_joltage() {
         assert_eq!(find_max_joltage_part1("987654321111111"), 98);
@@ -132,10 +153,35 @@ mod tests {
         assert_eq!(result, 357);
     }
 
+    fn reference(input: &str, k: usize) -> u64 {
+        process_input(Cursor::new(input), |line| {
+            max_joltage_combinations(line.as_bytes(), k, 0)
+        })
+    }
+
+    #[test]
+    fn test_differential() {
+        let parts = [
+            Part::new(
+                "day03 part 1",
+                |input: &str| reference(input, 2),
+                |input: &str| process_input(Cursor::new(input), find_max_joltage_part1),
+            ),
+            Part::new(
+                "day03 part 2",
+                |input: &str| reference(input, 12),
+                |input: &str| process_input(Cursor::new(input), find_max_joltage_part2),
+            ),
+        ];
+        // Short banks keep the combinations few, and repeated digits make
+        // for plenty of ties
+        let inputs = (0..20).map(|seed| generate::banks(&mut Rng::new(seed), 10, 16));
+        diff::check(&parts, inputs).unwrap_or_else(|m| panic!("{m}"));
+    }
+
     #[test]
     fn test_part1() {
-        let file = File::open("../inputs/day03.txt").expect("Failed to read input file");
-        let reader = BufReader::new(file);
+        let reader = read_input(3);
         let result = process_input(reader, find_max_joltage_part1);
         assert_eq!(result, 17316);
     }
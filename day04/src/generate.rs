@@ -0,0 +1,34 @@
+use aoc::rng::Rng;
+
+// A `width` x `height` grid where each cell holds a roll `@` with chance
+// `density` percent, and is empty `.` otherwise
+pub fn roll_grid(rng: &mut Rng, width: usize, height: usize, density: u64) -> String {
+    (0..height)
+        .map(|_| {
+            let mut row: String = (0..width)
+                .map(|_| if rng.chance(density, 100) { '@' } else { '.' })
+                .collect();
+            row.push('\n');
+            row
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{count_accessible_rolls, count_and_remove_accessible_rolls, read_grid};
+    use std::io::Cursor;
+
+    #[test]
+    fn test_roll_grid() {
+        let text = roll_grid(&mut Rng::new(4), 30, 20, 70);
+        let grid = read_grid(Cursor::new(&text));
+        assert_eq!((grid.width(), grid.height()), (30, 20));
+
+        let rolls = text.matches('@').count();
+        let accessible = count_accessible_rolls(&mut grid.clone());
+        let removed = count_and_remove_accessible_rolls(&mut grid.clone());
+        assert!(0 < accessible && accessible <= removed && removed <= rolls);
+    }
+}
@@ -1,54 +1,34 @@
+mod generate;
+
+use aoc::args::Args;
+use aoc::grid::{Grid, Point};
+use aoc::input::{Input, read_input};
+use aoc::rng::Rng;
 use std::collections::HashSet;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::BufRead;
 
 fn count_accessible_rolls_with<F, G>(
-    grid: &mut [Vec<u8>],
-    targets: &HashSet<u8>,
+    grid: &mut Grid<char>,
+    targets: &HashSet<char>,
     f: F,
     after: G,
 ) -> usize
 where
-    F: Fn(&mut [Vec<u8>], usize, usize),
-    G: Fn(&mut [Vec<u8>]),
+    F: Fn(&mut Grid<char>, Point),
+    G: Fn(&mut Grid<char>),
 {
-    let rows = grid.len();
-    if rows == 0 {
-        return 0;
-    }
-    let cols = grid[0].len();
-
     let mut count = 0;
 
-    for r in 0..rows {
-        for c in 0..cols {
-            if targets.contains(&grid[r][c]) {
-                let mut adjacent_count = 0;
-
-                for dr in -1..=1 {
-                    for dc in -1..=1 {
-                        if dr == 0 && dc == 0 {
-                            continue;
-                        }
-
-                        let nr = r as i32 + dr;
-                        let nc = c as i32 + dc;
-
-                        if nr >= 0
-                            && nr < rows as i32
-                            && nc >= 0
-                            && nc < cols as i32
-                            && targets.contains(&grid[nr as usize][nc as usize])
-                        {
-                            adjacent_count += 1;
-                        }
-                    }
-                }
+    for p in grid.points() {
+        if targets.contains(&grid[p]) {
+            let adjacent_count = grid
+                .neighbours8(p)
+                .filter(|&q| targets.contains(&grid[q]))
+                .count();
 
-                if adjacent_count < 4 {
-                    count += 1;
-                    f(grid, r, c);
-                }
+            if adjacent_count < 4 {
+                count += 1;
+                f(grid, p);
             }
         }
     }
@@ -58,26 +38,24 @@ where
     count
 }
 
-fn count_accessible_rolls(grid: &mut [Vec<u8>]) -> usize {
-    let targets = HashSet::from([b'@']);
-    count_accessible_rolls_with(grid, &targets, |_, _, _| {}, |_| {})
+fn count_accessible_rolls(grid: &mut Grid<char>) -> usize {
+    let targets = HashSet::from(['@']);
+    count_accessible_rolls_with(grid, &targets, |_, _| {}, |_| {})
 }
 
-fn count_and_remove_accessible_rolls(grid: &mut [Vec<u8>]) -> usize {
-    let targets = HashSet::from([b'@', b'R']);
+fn count_and_remove_accessible_rolls(grid: &mut Grid<char>) -> usize {
+    let targets = HashSet::from(['@', 'R']);
     let mut sum = 0;
 
     loop {
         let count = count_accessible_rolls_with(
             grid,
             &targets,
-            |g, r, c| g[r][c] = b'R',
+            |g, p| g[p] = 'R',
             |g| {
-                for row in g {
-                    for cell in row {
-                        if *cell == b'R' {
-                            *cell = b'.';
-                        }
+                for p in g.points() {
+                    if g[p] == 'R' {
+                        g[p] = '.';
                     }
                 }
             },
@@ -91,29 +69,27 @@ fn count_and_remove_accessible_rolls(grid: &mut [Vec<u8>]) -> usize {
     }
 }
 
-fn read_grid<R: BufRead>(reader: R) -> Vec<Vec<u8>> {
-    let mut grid = Vec::new();
-
-    for line in reader.lines() {
-        let line = line.expect("Failed to read line");
-        if !line.is_empty() {
-            grid.push(line.into_bytes());
-        }
-    }
-
-    grid
-}
-
-fn read_input() -> BufReader<File> {
-    let file = File::open("inputs/day04.txt").expect("Failed to open input file");
-    BufReader::new(file)
+fn read_grid<R: BufRead>(reader: R) -> Grid<char> {
+    let input = Input::from_reader(reader);
+    Grid::parse(input.text()).unwrap_or_else(|e| panic!("Not a rectangular grid: {e}"))
 }
 
 fn main() {
-    let mut grid = read_grid(read_input());
+    let args = Args::from_env();
+
+    if args.is_command("generate") {
+        let mut rng = Rng::new(args.number("--seed").expect("Missing --seed"));
+        let width = args.number::<usize>("--width").unwrap_or(140);
+        let height = args.number::<usize>("--height").unwrap_or(140);
+        let density = args.number("--density").unwrap_or(65);
+        print!("{}", generate::roll_grid(&mut rng, width, height, density));
+        return;
+    }
+
+    let mut grid = read_grid(read_input(4));
     let result = count_accessible_rolls(&mut grid);
     println!("Part 1: {result}");
-    let mut grid = read_grid(read_input());
+    let mut grid = read_grid(read_input(4));
     let result = count_and_remove_accessible_rolls(&mut grid);
     println!("Part 2: {result}");
 }
@@ -121,8 +97,52 @@ fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc::diff::{self, Part};
     use std::io::Cursor;
 
+    // Reference: positions of the rolls with fewer than four rolls among the
+    // eight cells around them, on plain rows of bytes
+    fn accessible_naive(rows: &[Vec<u8>]) -> Vec<(usize, usize)> {
+        let mut accessible = Vec::new();
+        for (y, row) in rows.iter().enumerate() {
+            for (x, &cell) in row.iter().enumerate() {
+                if cell != b'@' {
+                    continue;
+                }
+                let mut adjacent = 0;
+                for ny in y.saturating_sub(1)..=y + 1 {
+                    for nx in x.saturating_sub(1)..=x + 1 {
+                        let roll = rows.get(ny).and_then(|r| r.get(nx)) == Some(&b'@');
+                        adjacent += ((ny, nx) != (y, x) && roll) as usize;
+                    }
+                }
+                if adjacent < 4 {
+                    accessible.push((y, x));
+                }
+            }
+        }
+        accessible
+    }
+
+    fn rows(input: &str) -> Vec<Vec<u8>> {
+        Input::new(input)
+            .lines()
+            .map(|l| l.bytes().collect())
+            .collect()
+    }
+
+    // Removing a roll only frees its neighbours, so taking them one at a time
+    // ends with the same rolls gone as taking them in rounds
+    fn remove_naive(input: &str) -> usize {
+        let mut rows = rows(input);
+        let mut removed = 0;
+        while let Some(&(y, x)) = accessible_naive(&rows).first() {
+            rows[y][x] = b'.';
+            removed += 1;
+        }
+        removed
+    }
+
     #[test]
     fn test_example() {
         let input = "..@@.@@@@.\n@@@.@.@.@@\n@@@@@.@.@@\n@.@@@@..@.\n@@.@@@@.@@\n.@@@@@@@.@\n.@.@.@.@@@\n@.@@@.@@@@\n.@@@@@@@@.\n@.@.@@@.@.";
@@ -130,12 +150,28 @@ mod tests {
         let mut grid = read_grid(reader);
         let result = count_accessible_rolls(&mut grid);
         assert_eq!(result, 13);
+        assert_eq!(remove_naive(input), 43);
+    }
+
+    #[test]
+    fn test_differential() {
+        let parts = [
+            Part::new(
+                "day04 part 1",
+                |input: &str| accessible_naive(&rows(input)).len(),
+                |input: &str| count_accessible_rolls(&mut read_grid(Cursor::new(input))),
+            ),
+            Part::new("day04 part 2", remove_naive, |input: &str| {
+                count_and_remove_accessible_rolls(&mut read_grid(Cursor::new(input)))
+            }),
+        ];
+        let inputs = (0..20).map(|seed| generate::roll_grid(&mut Rng::new(seed), 12, 10, 65));
+        diff::check(&parts, inputs).unwrap_or_else(|m| panic!("{m}"));
     }
 
     #[test]
     fn test_part1() {
-        let file = File::open("../inputs/day04.txt").expect("Failed to read input file");
-        let reader = BufReader::new(file);
+        let reader = read_input(4);
         let mut grid = read_grid(reader);
         let result = count_accessible_rolls(&mut grid);
         println!("Part 1 result: {result}");
@@ -0,0 +1,45 @@
+use aoc::rng::Rng;
+
+// A database of `ranges` fresh ID ranges of at most `width` IDs, a blank
+// line, then `ids` available IDs, all IDs at most `max`
+pub fn database(rng: &mut Rng, ranges: usize, ids: usize, max: u64, width: u64) -> String {
+    let mut out = String::new();
+    for _ in 0..ranges {
+        let start = rng.below(max);
+        let end = start + rng.below(width.max(1)).min(max - start);
+        out += &format!("{start}-{end}\n");
+    }
+    out.push('\n');
+    for _ in 0..ids {
+        out += &format!("{}\n", rng.between(0, max));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_database() {
+        let text = database(&mut Rng::new(5), 40, 300, 10_000, 500);
+        let (ranges, ids) = text.split_once("\n\n").unwrap();
+        let ranges: Vec<(u64, u64)> = ranges
+            .lines()
+            .map(|l| {
+                let (a, b) = l.split_once('-').unwrap();
+                (a.parse().unwrap(), b.parse().unwrap())
+            })
+            .collect();
+        assert_eq!(ranges.len(), 40);
+        assert!(
+            ranges
+                .iter()
+                .all(|&(a, b)| a <= b && b - a < 500 && b <= 10_000)
+        );
+
+        let ids: Vec<u64> = ids.lines().map(|l| l.parse().unwrap()).collect();
+        assert_eq!(ids.len(), 300);
+        assert!(ids.iter().all(|&id| id <= 10_000));
+    }
+}
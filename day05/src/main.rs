@@ -1,14 +1,15 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+mod generate;
 
-fn parse_and_merge_ranges<R: BufRead>(reader: R) -> Vec<(u64, u64)> {
-    let mut lines = reader.lines().map(|l| l.expect("Failed to read line"));
+use aoc::args::Args;
+use aoc::input::{Input, read_input};
+use aoc::rng::Rng;
+use std::io::BufRead;
+
+// Sorted ranges of the section's `a-b` lines, overlapping ones merged
+fn merge_ranges(section: &str) -> Vec<(u64, u64)> {
     let mut ranges: Vec<(u64, u64)> = Vec::new();
 
-    for line in lines.by_ref() {
-        if line.is_empty() {
-            break;
-        }
+    for line in section.lines() {
         if let Some((start, end)) = line.split_once('-') {
             ranges.push((start.parse().unwrap(), end.parse().unwrap()));
         }
@@ -19,24 +20,19 @@ fn parse_and_merge_ranges<R: BufRead>(reader: R) -> Vec<(u64, u64)> {
     ranges
 }
 
-fn count_fresh_ingredients<R: BufRead>(reader: R) -> usize {
-    let mut lines = reader.lines().map(|l| l.expect("Failed to read line"));
-    let mut ranges: Vec<(u64, u64)> = Vec::new();
-
-    for line in lines.by_ref() {
-        if line.is_empty() {
-            break;
-        }
-        if let Some((start, end)) = line.split_once('-') {
-            ranges.push((start.parse().unwrap(), end.parse().unwrap()));
-        }
-    }
+fn parse_and_merge_ranges<R: BufRead>(reader: R) -> Vec<(u64, u64)> {
+    let input = Input::from_reader(reader);
+    merge_ranges(input.sections().next().unwrap_or(""))
+}
 
-    ranges.sort_unstable();
-    ranges.dedup_by(|b, a| (b.0 <= a.1 + 1).then(|| a.1 = a.1.max(b.1)).is_some());
+// The input has two sections: fresh ID ranges, then the available IDs
+fn count_fresh_ingredients<R: BufRead>(reader: R) -> usize {
+    let input = Input::from_reader(reader);
+    let mut sections = input.sections();
+    let ranges = merge_ranges(sections.next().unwrap_or(""));
 
-    lines
-        .filter(|line| !line.is_empty())
+    sections
+        .flat_map(str::lines)
         .map(|line| line.parse::<u64>().unwrap())
         .filter(|&id| {
             let pos = ranges.partition_point(|(s, _)| *s <= id);
@@ -52,23 +48,61 @@ fn count_all_fresh_ids<R: BufRead>(reader: R) -> u64 {
         .sum()
 }
 
-fn read_input() -> BufReader<File> {
-    let file = File::open("inputs/day05.txt").expect("Failed to open input file");
-    BufReader::new(file)
-}
-
 fn main() {
-    let result = count_fresh_ingredients(read_input());
+    let args = Args::from_env();
+
+    if args.is_command("generate") {
+        let mut rng = Rng::new(args.number("--seed").expect("Missing --seed"));
+        let ranges = args.number::<usize>("--ranges").unwrap_or(180);
+        let ids = args.number::<usize>("--ids").unwrap_or(1000);
+        let max = args.number("--max").unwrap_or(500_000_000_000_000);
+        let width = args.number("--width").unwrap_or(max / 100);
+        print!("{}", generate::database(&mut rng, ranges, ids, max, width));
+        return;
+    }
+
+    let result = count_fresh_ingredients(read_input(5));
     println!("Part 1: {result}");
-    let result = count_all_fresh_ids(read_input());
+    let result = count_all_fresh_ids(read_input(5));
     println!("Part 2: {result}");
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc::diff::{self, Part};
+    use std::collections::HashSet;
     use std::io::Cursor;
 
+    // Reference: the unmerged ranges and every available ID
+    fn parse_naive(input: &str) -> (Vec<(u64, u64)>, Vec<u64>) {
+        let input = Input::new(input);
+        let mut sections = input.sections();
+        let ranges = sections
+            .next()
+            .unwrap_or("")
+            .lines()
+            .filter_map(|l| l.split_once('-'))
+            .map(|(a, b)| (a.parse().unwrap(), b.parse().unwrap()))
+            .collect();
+        let ids = sections.flat_map(str::lines).map(|l| l.parse().unwrap());
+        (ranges, ids.collect())
+    }
+
+    fn fresh_naive(input: &str) -> usize {
+        let (ranges, ids) = parse_naive(input);
+        ids.iter()
+            .filter(|&&id| ranges.iter().any(|&(a, b)| a <= id && id <= b))
+            .count()
+    }
+
+    // Every ID of every range, so only for narrow ranges
+    fn all_fresh_naive(input: &str) -> u64 {
+        let (ranges, _) = parse_naive(input);
+        let ids: HashSet<u64> = ranges.iter().flat_map(|&(a, b)| a..=b).collect();
+        ids.len() as u64
+    }
+
     #[test]
     fn test_example() {
         let input = "3-5\n10-14\n16-20\n12-18\n\n1\n5\n8\n11\n17\n32";
@@ -79,20 +113,39 @@ mod tests {
         let reader = Cursor::new(input);
         let result = count_all_fresh_ids(reader);
         assert_eq!(result, 14);
+
+        // CRLF line endings leave the separator line blank after normalizing
+        let crlf = input.replace('\n', "\r\n");
+        assert_eq!(count_fresh_ingredients(Cursor::new(&crlf)), 3);
+        assert_eq!(count_all_fresh_ids(Cursor::new(&crlf)), 14);
+    }
+
+    #[test]
+    fn test_differential() {
+        let parts = [
+            Part::new("day05 part 1", fresh_naive, |input: &str| {
+                count_fresh_ingredients(Cursor::new(input))
+            }),
+            Part::new("day05 part 2", all_fresh_naive, |input: &str| {
+                count_all_fresh_ids(Cursor::new(input))
+            }),
+        ];
+        // Narrow ranges over a small span overlap and touch often
+        let inputs =
+            (0..20).map(|seed| generate::database(&mut Rng::new(seed), 30, 50, 2_000, 100));
+        diff::check(&parts, inputs).unwrap_or_else(|m| panic!("{m}"));
     }
 
     #[test]
     fn test_part1() {
-        let file = File::open("../inputs/day05.txt").expect("Failed to read input file");
-        let reader = BufReader::new(file);
+        let reader = read_input(5);
         let result = count_fresh_ingredients(reader);
         assert_eq!(result, 690);
     }
 
     #[test]
     fn test_part2() {
-        let file = File::open("../inputs/day05.txt").expect("Failed to read input file");
-        let reader = BufReader::new(file);
+        let reader = read_input(5);
         let result = count_all_fresh_ids(reader);
         println!("Part 2 result: {result}");
     }
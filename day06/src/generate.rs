@@ -0,0 +1,63 @@
+use aoc::rng::Rng;
+
+// A worksheet of `problems` problems side by side, each `rows` numbers of up
+// to `digits` digits above its operator. A problem is as wide as its longest
+// number, and its numbers are all aligned left or all aligned right.
+pub fn worksheet(rng: &mut Rng, problems: usize, rows: usize, digits: u32) -> String {
+    // Read either way, a product has at most rows * digits digits
+    assert!(rows * digits as usize <= 15, "Answers would overflow");
+    let mut lines = vec![String::new(); rows + 1];
+
+    for p in 0..problems {
+        let numbers: Vec<String> = (0..rows)
+            .map(|_| {
+                let d = rng.between(1, digits as u64) as u32;
+                rng.between(10u64.pow(d - 1), 10u64.pow(d) - 1).to_string()
+            })
+            .collect();
+        let width = numbers.iter().map(|n| n.len()).max().unwrap_or(1);
+        let left = rng.chance(1, 2);
+        let operator = if rng.chance(1, 2) { '+' } else { '*' };
+
+        for (line, number) in lines.iter_mut().zip(&numbers) {
+            if p > 0 {
+                line.push(' ');
+            }
+            if left {
+                *line += &format!("{number:<width$}");
+            } else {
+                *line += &format!("{number:>width$}");
+            }
+        }
+        let last = &mut lines[rows];
+        if p > 0 {
+            last.push(' ');
+        }
+        *last += &format!("{operator:<width$}");
+    }
+
+    lines.iter().map(|line| format!("{line}\n")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_worksheet() {
+        let text = worksheet(&mut Rng::new(6), 30, 4, 3);
+        let lines: Vec<Vec<&str>> = text
+            .lines()
+            .map(|l| l.split_whitespace().collect())
+            .collect();
+        assert_eq!(lines.len(), 5);
+        assert!(lines.iter().all(|fields| fields.len() == 30));
+        assert!(
+            lines[..4]
+                .iter()
+                .flatten()
+                .all(|n| n.len() <= 3 && n.parse::<u64>().is_ok())
+        );
+        assert!(lines[4].iter().all(|&op| op == "+" || op == "*"));
+    }
+}
@@ -1,21 +1,16 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+mod generate;
 
-fn solve_worksheet<R: BufRead>(reader: R) -> (u64, u64) {
-    let lines: Vec<String> = reader.lines().map(|l| l.unwrap()).collect();
-    let width = lines.iter().map(|l| l.len()).max().unwrap_or(0);
-
-    let grid: Vec<Vec<char>> = lines
-        .iter()
-        .map(|line| {
-            let mut chars = line.chars().collect::<Vec<_>>();
-            chars.resize(width, ' ');
-            chars
-        })
-        .collect();
+use aoc::args::Args;
+use aoc::grid::Grid;
+use aoc::input::{Input, read_input};
+use aoc::rng::Rng;
+use std::io::BufRead;
 
-    let height = grid.len();
-    let is_empty_col = |col| (0..height).all(|row| grid[row][col] == ' ');
+fn solve_worksheet<R: BufRead>(reader: R) -> (u64, u64) {
+    let input = Input::from_reader(reader);
+    let grid = Grid::parse_padded(input.text(), ' ');
+    let width = grid.width();
+    let is_empty_col = |col| grid.column(col).all(|&ch| ch == ' ');
 
     let mut part1_total = 0u64;
     let mut part2_total = 0u64;
@@ -35,8 +30,8 @@ fn solve_worksheet<R: BufRead>(reader: R) -> (u64, u64) {
         let mut part1_numbers = Vec::new();
         let mut operator = None;
 
-        for row in 0..height {
-            let text: String = (start..col).map(|c| grid[row][c]).collect();
+        for row in grid.rows() {
+            let text: String = row[start..col].iter().collect();
             match text.trim() {
                 "+" | "*" => operator = text.trim().chars().next(),
                 t => {
@@ -50,12 +45,7 @@ fn solve_worksheet<R: BufRead>(reader: R) -> (u64, u64) {
         let part2_numbers: Vec<u64> = (start..col)
             .rev()
             .filter_map(|c| {
-                let digits: String = (0..height)
-                    .filter_map(|row| {
-                        let ch = grid[row][c];
-                        ch.is_ascii_digit().then_some(ch)
-                    })
-                    .collect();
+                let digits: String = grid.column(c).filter(|ch| ch.is_ascii_digit()).collect();
                 digits.parse().ok()
             })
             .collect();
@@ -77,13 +67,19 @@ fn solve_worksheet<R: BufRead>(reader: R) -> (u64, u64) {
     (part1_total, part2_total)
 }
 
-fn read_input() -> BufReader<File> {
-    let file = File::open("../inputs/day06.txt").expect("Failed to open input file");
-    BufReader::new(file)
-}
-
 fn main() {
-    let (part1, part2) = solve_worksheet(read_input());
+    let args = Args::from_env();
+
+    if args.is_command("generate") {
+        let mut rng = Rng::new(args.number("--seed").expect("Missing --seed"));
+        let problems = args.number::<usize>("--problems").unwrap_or(1000);
+        let rows = args.number::<usize>("--rows").unwrap_or(4);
+        let digits = args.number::<u32>("--digits").unwrap_or(3);
+        print!("{}", generate::worksheet(&mut rng, problems, rows, digits));
+        return;
+    }
+
+    let (part1, part2) = solve_worksheet(read_input(6));
     println!("Part 1: {part1}");
     println!("Part 2: {part2}");
 }
@@ -91,8 +87,69 @@ fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc::diff::{self, Part};
     use std::io::Cursor;
 
+    fn apply(operator: char, numbers: impl Iterator<Item = u64>) -> u64 {
+        match operator {
+            '+' => numbers.sum(),
+            _ => numbers.product(),
+        }
+    }
+
+    // Reference for part 1: read row-wise, the problems are just the
+    // whitespace-separated columns, with the operators on the last line
+    fn part1_naive(input: &str) -> u64 {
+        let input = Input::new(input);
+        let rows: Vec<Vec<&str>> = input
+            .non_empty_lines()
+            .map(|l| l.split_whitespace().collect())
+            .collect();
+        let Some((operators, numbers)) = rows.split_last() else {
+            return 0;
+        };
+        (0..operators.len())
+            .map(|p| {
+                let column = numbers.iter().map(|row| row[p].parse::<u64>().unwrap());
+                apply(operators[p].chars().next().unwrap(), column)
+            })
+            .sum()
+    }
+
+    // Reference for part 2: transpose the worksheet, so that each number is
+    // one line and problems are separated by blank lines, then read it as is
+    fn part2_naive(input: &str) -> u64 {
+        let lines: Vec<Vec<char>> = Input::new(input)
+            .non_empty_lines()
+            .map(|l| l.chars().collect())
+            .collect();
+        let width = lines.iter().map(Vec::len).max().unwrap_or(0);
+        let transposed: Vec<String> = (0..width)
+            .rev()
+            .map(|x| {
+                lines
+                    .iter()
+                    .map(|l| l.get(x).copied().unwrap_or(' '))
+                    .collect()
+            })
+            .collect();
+
+        transposed
+            .split(|column| column.trim().is_empty())
+            .filter(|problem| !problem.is_empty())
+            .map(|problem| {
+                let operator = problem
+                    .iter()
+                    .find_map(|c| c.chars().last().filter(|&ch| ch != ' '));
+                let numbers = problem.iter().filter_map(|c| {
+                    let digits: String = c.chars().filter(char::is_ascii_digit).collect();
+                    digits.parse().ok()
+                });
+                apply(operator.unwrap_or('+'), numbers)
+            })
+            .sum()
+    }
+
     #[test]
     fn test_example() {
         let input = "123  328  51  64 \n 45  64  387  23 \n  6  98  215  314\n*    +    *    +  ";
@@ -100,11 +157,42 @@ mod tests {
         let (part1, part2) = solve_worksheet(reader);
         assert_eq!(part1, 4277556);
         assert_eq!(part2, 3263827);
+        assert_eq!(part1_naive(input), 4277556);
+        assert_eq!(part2_naive(input), 3263827);
+    }
+
+    #[test]
+    fn test_differential() {
+        // Each row needs one field per operator, or part 1 reads nothing
+        // sensible, so shrinking may only drop rows of numbers
+        let valid = |input: &str| {
+            let input = Input::new(input);
+            let fields: Vec<usize> = input
+                .non_empty_lines()
+                .map(|l| l.split_whitespace().count())
+                .collect();
+            let operators = input.non_empty_lines().last().unwrap_or("");
+            fields.windows(2).all(|w| w[0] == w[1])
+                && !operators.is_empty()
+                && operators.chars().all(|c| matches!(c, '+' | '*' | ' '))
+        };
+        let parts = [
+            Part::new("day06 part 1", part1_naive, |input: &str| {
+                solve_worksheet(Cursor::new(input)).0
+            })
+            .valid_when(valid),
+            Part::new("day06 part 2", part2_naive, |input: &str| {
+                solve_worksheet(Cursor::new(input)).1
+            })
+            .valid_when(valid),
+        ];
+        let inputs = (0..20).map(|seed| generate::worksheet(&mut Rng::new(seed), 15, 4, 3));
+        diff::check(&parts, inputs).unwrap_or_else(|m| panic!("{m}"));
     }
 
     #[test]
     fn test_part1() {
-        let (part1, part2) = solve_worksheet(read_input());
+        let (part1, part2) = solve_worksheet(read_input(6));
         println!("Part 1: {part1}");
         println!("Part 2: {part2}");
         assert_eq!(part1, 6169101504608);
@@ -112,7 +200,7 @@ mod tests {
 
     #[test]
     fn test_part2() {
-        let (_part1, part2) = solve_worksheet(read_input());
+        let (_part1, part2) = solve_worksheet(read_input(6));
         println!("Part 2: {part2}");
         assert_eq!(part2, 10442199710797);
     }
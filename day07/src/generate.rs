@@ -0,0 +1,44 @@
+use aoc::rng::Rng;
+
+// A classic manifold `width` wide and `height` tall: the entry `S` centred on
+// the top row, and splitters `^` on every other row below it, each cell with
+// chance `density` percent. Splitters never touch the side walls or each
+// other, as in puzzle inputs.
+pub fn manifold(rng: &mut Rng, width: usize, height: usize, density: u64) -> String {
+    let mut out = String::new();
+    for row in 0..height {
+        let mut line = vec!['.'; width];
+        if row == 0 {
+            line[width / 2] = 'S';
+        } else if row % 2 == 0 {
+            for col in 1..width.saturating_sub(1) {
+                if line[col - 1] != '^' && rng.chance(density, 100) {
+                    line[col] = '^';
+                }
+            }
+        }
+        out.extend(line);
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{count_splits, parse_manifold, sweep_timelines, timeline_counts};
+    use std::io::Cursor;
+
+    #[test]
+    fn test_manifold() {
+        let text = manifold(&mut Rng::new(7), 41, 40, 30);
+        let (grid, start) = parse_manifold(Cursor::new(&text)).unwrap();
+        assert_eq!((grid.height(), grid.width(), start), (40, 41, (0, 20)));
+        assert!(!text.contains("^^"));
+
+        let splits = count_splits(Cursor::new(&text));
+        assert!(0 < splits && splits <= text.matches('^').count());
+        let timelines = sweep_timelines(&grid, start).unwrap();
+        assert_eq!(timeline_counts(&grid, start).unwrap().1, timelines);
+    }
+}
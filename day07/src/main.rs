@@ -1,143 +1,694 @@
-use std::collections::HashSet;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+mod generate;
 
-fn count_splits<R: BufRead>(reader: R) -> usize {
-    let grid: Vec<Vec<char>> = reader
-        .lines()
-        .map(|l| l.unwrap().chars().collect())
-        .collect();
-    let (height, width) = (grid.len(), grid[0].len());
+use aoc::args::Args;
+use aoc::grid::{Grid, Point};
+use aoc::input::{Input, read_input};
+use aoc::rng::Rng;
+use std::collections::{HashMap, HashSet};
+use std::io::BufRead;
 
-    let start = grid
-        .iter()
-        .enumerate()
-        .find_map(|(r, row)| row.iter().position(|&c| c == 'S').map(|c| (r, c)))
-        .unwrap();
+// Parse the manifold into a grid and locate the `S` entry point. Short lines
+// are padded with empty cells. An empty input has no manifold at all.
+fn parse_manifold<R: BufRead>(reader: R) -> Option<(Grid<char>, (usize, usize))> {
+    let input = Input::from_reader(reader);
+    if input.text().is_empty() {
+        return None;
+    }
+    let grid = Grid::parse_padded(input.text(), '.');
+    let start = grid.find(&'S').expect("No `S` in the manifold");
 
-    let mut splits = 0;
-    let mut beams = vec![start];
-    let mut visited = HashSet::new();
+    Some((grid, (start.y as usize, start.x as usize)))
+}
 
-    while !beams.is_empty() {
-        beams = beams
-            .iter()
-            .filter_map(|&(row, col)| {
-                (row..height).find_map(|r| {
-                    (grid[r][col] == '^').then(|| {
-                        if visited.insert((r, col)) {
-                            splits += 1;
-                            [
-                                (col > 0).then_some((r, col - 1)),
-                                (col < width - 1).then_some((r, col + 1)),
-                            ]
-                            .into_iter()
-                            .flatten()
-                            .collect()
-                        } else {
-                            vec![]
-                        }
-                    })
-                })
-            })
-            .flatten()
-            .collect();
+// Direction a beam is travelling in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Heading {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Heading {
+    fn offset(self) -> Point {
+        match self {
+            Heading::Up => Point::UP,
+            Heading::Down => Point::DOWN,
+            Heading::Left => Point::LEFT,
+            Heading::Right => Point::RIGHT,
+        }
+    }
+
+    fn is_vertical(self) -> bool {
+        matches!(self, Heading::Up | Heading::Down)
+    }
+
+    // The two directions perpendicular to this one, where a splitter sends its beams
+    fn sides(self) -> [Heading; 2] {
+        if self.is_vertical() {
+            [Heading::Left, Heading::Right]
+        } else {
+            [Heading::Up, Heading::Down]
+        }
+    }
+
+    // New heading after bouncing off a `/` or `\` mirror
+    fn reflect(self, mirror: char) -> Heading {
+        match (mirror, self) {
+            ('/', Heading::Up) | ('\\', Heading::Down) => Heading::Right,
+            ('/', Heading::Down) | ('\\', Heading::Up) => Heading::Left,
+            ('/', Heading::Left) | ('\\', Heading::Right) => Heading::Down,
+            ('/', Heading::Right) | ('\\', Heading::Left) => Heading::Up,
+            _ => unreachable!("not a mirror: {mirror}"),
+        }
+    }
+}
+
+// A beam occupying a cell while travelling in some direction
+type Beam = (usize, usize, Heading);
+
+type Cells = HashSet<(usize, usize)>;
+
+// Number of timelines passing through each cell
+type CellCounts = HashMap<(usize, usize), u128>;
+
+// Cells crossed by a beam, mapped to whether any beam crosses them vertically
+type Path = HashMap<(usize, usize), bool>;
+
+// A loop of beams that never leaves the manifold, as the cells it visits
+#[derive(Debug, PartialEq, Eq)]
+struct Cycle(Vec<(usize, usize)>);
+
+impl std::fmt::Display for Cycle {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "beam loops forever through")?;
+        for (r, c) in &self.0 {
+            write!(f, " ({r}, {c})")?;
+        }
+        Ok(())
+    }
+}
+
+fn cell(grid: &Grid<char>, row: usize, col: usize) -> char {
+    grid[Point::new(col as isize, row as isize)]
+}
+
+// Neighbouring cell in the given direction, or None past the manifold's edge
+fn step(grid: &Grid<char>, row: usize, col: usize, heading: Heading) -> Option<(usize, usize)> {
+    let next = Point::new(col as isize, row as isize) + heading.offset();
+    grid.contains(next)
+        .then_some((next.y as usize, next.x as usize))
+}
+
+// Beams produced by a beam in its current cell; None stands for a beam
+// leaving the manifold, which ends one timeline.
+//
+// - `^` splits the beam into its two side cells, keeping its heading;
+//   a side beam that would start outside the manifold is lost
+// - `+` does the same and also lets the beam pass straight through
+// - `/` and `\` reflect the beam by 90 degrees
+// - `#` absorbs the beam
+// - anything else (`.`, `S`) lets the beam continue
+fn advance(grid: &Grid<char>, (row, col, heading): Beam) -> Vec<Option<Beam>> {
+    let forward = |heading| step(grid, row, col, heading).map(|(r, c)| (r, c, heading));
+    let sides = || {
+        heading
+            .sides()
+            .into_iter()
+            .filter_map(move |side| step(grid, row, col, side))
+            .map(move |(r, c)| Some((r, c, heading)))
+    };
+
+    match cell(grid, row, col) {
+        '^' => sides().collect(),
+        '+' => sides().chain([forward(heading)]).collect(),
+        m @ ('/' | '\\') => vec![forward(heading.reflect(m))],
+        '#' => vec![],
+        _ => vec![forward(heading)],
+    }
+}
+
+fn is_splitter(ch: char) -> bool {
+    ch == '^' || ch == '+'
+}
+
+// Every beam reachable from the start, in topological order, or the first
+// cycle found among them
+struct Simulation {
+    beams: Vec<Beam>,
+    cycle: Option<Cycle>,
+}
+
+// Run the beam from the start with an iterative depth-first search so that
+// loops between mirrors are detected instead of followed forever
+fn simulate(grid: &Grid<char>, start: (usize, usize)) -> Simulation {
+    let start = (start.0, start.1, Heading::Down);
+    let mut on_stack: HashSet<Beam> = HashSet::from([start]);
+    let mut done: HashSet<Beam> = HashSet::new();
+    let mut stack = vec![(start, advance(grid, start).into_iter().flatten())];
+    let mut order = Vec::new();
+    let mut cycle = None;
+
+    while let Some((beam, next)) = stack.last_mut() {
+        let beam = *beam;
+        match next.next() {
+            Some(succ) if on_stack.contains(&succ) => {
+                if cycle.is_none() {
+                    let from = stack.iter().position(|(b, _)| *b == succ).unwrap();
+                    let cells = stack[from..].iter().map(|&((r, c, _), _)| (r, c));
+                    cycle = Some(Cycle(cells.collect()));
+                }
+            }
+            Some(succ) if !done.contains(&succ) => {
+                on_stack.insert(succ);
+                stack.push((succ, advance(grid, succ).into_iter().flatten()));
+            }
+            Some(_) => {}
+            None => {
+                on_stack.remove(&beam);
+                done.insert(beam);
+                order.push(beam);
+                stack.pop();
+            }
+        }
     }
 
-    splits
+    order.reverse();
+    Simulation {
+        beams: order,
+        cycle,
+    }
 }
 
-fn count_timelines<R: BufRead>(reader: R) -> usize {
-    let grid: Vec<Vec<char>> = reader
-        .lines()
-        .map(|l| l.unwrap().chars().collect())
+// Cells any beam passes through and the splitters hit along the way
+fn trace_beams(grid: &Grid<char>, start: (usize, usize)) -> (Path, Cells) {
+    let mut path = Path::new();
+    for (r, c, heading) in simulate(grid, start).beams {
+        *path.entry((r, c)).or_insert(false) |= heading.is_vertical();
+    }
+    let hit = path
+        .keys()
+        .copied()
+        .filter(|&(r, c)| is_splitter(cell(grid, r, c)))
         .collect();
-    let (height, width) = (grid.len(), grid[0].len());
 
-    let start = grid
-        .iter()
-        .enumerate()
-        .find_map(|(r, row)| row.iter().position(|&c| c == 'S').map(|c| (r, c)))
-        .unwrap();
+    (path, hit)
+}
+
+fn count_splits<R: BufRead>(reader: R) -> usize {
+    let Some((grid, start)) = parse_manifold(reader) else {
+        return 0;
+    };
+    trace_beams(&grid, start).1.len()
+}
+
+// Count the timelines passing through every cell, splitters included, along
+// with the total number of timelines leaving the manifold. A beam cycle
+// would make these infinite, so it is reported instead.
+fn timeline_counts(grid: &Grid<char>, start: (usize, usize)) -> Result<(CellCounts, u128), Cycle> {
+    let simulation = simulate(grid, start);
+    if let Some(cycle) = simulation.cycle {
+        return Err(cycle);
+    }
 
-    use std::collections::HashMap;
-    let mut current: HashMap<(usize, usize), usize> = HashMap::from([(start, 1)]);
+    let mut counts: HashMap<Beam, u128> = HashMap::new();
+    let mut cells = CellCounts::new();
     let mut total = 0;
+    counts.insert(simulation.beams[0], 1);
 
-    while !current.is_empty() {
-        let mut next: HashMap<(usize, usize), usize> = HashMap::new();
+    for beam in simulation.beams {
+        let count = counts[&beam];
+        *cells.entry((beam.0, beam.1)).or_insert(0) += count;
 
-        for (&(row, col), &count) in &current {
-            let r = (row..height).find(|&r| grid[r][col] == '^');
+        for next in advance(grid, beam) {
+            match next {
+                Some(next) => *counts.entry(next).or_insert(0) += count,
+                None => total += count,
+            }
+        }
+    }
 
-            if let Some(r) = r {
-                if col > 0 {
-                    *next.entry((r, col - 1)).or_insert(0) += count;
-                }
-                if col < width - 1 {
-                    *next.entry((r, col + 1)).or_insert(0) += count;
+    Ok((cells, total))
+}
+
+// Whether the manifold only uses the original `S`, `^` and `.` cells, so that
+// every beam travels downwards
+fn is_classic(grid: &Grid<char>) -> bool {
+    grid.iter().all(|(_, &ch)| matches!(ch, 'S' | '^' | '.'))
+}
+
+// Count the timelines of a classic manifold in one top-to-bottom sweep,
+// tracking how many timelines enter each column of the current row. Two
+// neighbouring splitters keep feeding each other, which is reported as a cycle.
+fn sweep_timelines(grid: &Grid<char>, start: (usize, usize)) -> Result<u128, Cycle> {
+    let width = grid.width();
+    let mut beams = vec![0u128; width];
+    beams[start.1] = 1;
+
+    for row in start.0..grid.height() {
+        let mut next = vec![0u128; width];
+
+        for (col, &count) in beams.iter().enumerate() {
+            if count == 0 {
+                continue;
+            }
+            if cell(grid, row, col) != '^' {
+                next[col] += count;
+                continue;
+            }
+            for side in [col.checked_sub(1), (col + 1 < width).then_some(col + 1)]
+                .into_iter()
+                .flatten()
+            {
+                if cell(grid, row, side) == '^' {
+                    return Err(Cycle(vec![(row, col), (row, side)]));
                 }
-            } else {
-                total += count;
+                next[side] += count;
             }
         }
 
-        current = next;
+        beams = next;
     }
 
-    total
+    Ok(beams.iter().sum())
 }
 
-fn read_input() -> BufReader<File> {
-    let file = File::open("../inputs/day07.txt").expect("Failed to open input file");
-    BufReader::new(file)
+fn count_timelines<R: BufRead>(reader: R) -> Result<u128, Cycle> {
+    let Some((grid, start)) = parse_manifold(reader) else {
+        return Ok(0);
+    };
+    if is_classic(&grid) {
+        sweep_timelines(&grid, start)
+    } else {
+        Ok(timeline_counts(&grid, start)?.1)
+    }
+}
+
+// Redraw the manifold with `|` for every vertical beam cell and `-` for every
+// horizontal one; hit splitters keep their glyph and splitters no beam ever
+// reaches are crossed out as `x`
+fn render_beams(grid: &Grid<char>, start: (usize, usize)) -> String {
+    let (path, hit) = trace_beams(grid, start);
+
+    grid.map(|p, &ch| {
+        let (r, c) = (p.y as usize, p.x as usize);
+        match ch {
+            _ if is_splitter(ch) && !hit.contains(&(r, c)) => 'x',
+            '.' => match path.get(&(r, c)) {
+                Some(true) => '|',
+                Some(false) => '-',
+                None => '.',
+            },
+            _ => ch,
+        }
+    })
+    .to_string()
+}
+
+// Heat level 1..=9 of a timeline count on a log scale relative to the busiest cell
+fn heat_level(count: u128, max: u128) -> usize {
+    if max <= 1 {
+        return 9;
+    }
+    1 + ((count as f64).ln() / (max as f64).ln() * 8.0).round() as usize
+}
+
+// Table of the number of timelines through every cell, right-aligned in
+// columns as wide as the largest count. Cells no beam reaches keep their glyph.
+fn render_heatmap(grid: &Grid<char>, start: (usize, usize)) -> Result<String, Cycle> {
+    let (cells, _) = timeline_counts(grid, start)?;
+    let max = cells.values().copied().max().unwrap_or(0);
+    let width = max.to_string().len();
+
+    let mut out = String::new();
+    for (y, row) in grid.rows().enumerate() {
+        let fields: Vec<String> = row
+            .iter()
+            .enumerate()
+            .map(|(x, ch)| match cells.get(&(y, x)) {
+                Some(count) => format!("{count:>width$}"),
+                None => format!("{ch:>width$}"),
+            })
+            .collect();
+        out += &fields.join(" ");
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+// Compact version of `render_heatmap`: the manifold redrawn with each
+// traversed cell replaced by its heat level
+fn render_heat_levels(grid: &Grid<char>, start: (usize, usize)) -> Result<String, Cycle> {
+    let (cells, _) = timeline_counts(grid, start)?;
+    let max = cells.values().copied().max().unwrap_or(0);
+
+    let heat = grid.map(|p, &ch| match cells.get(&(p.y as usize, p.x as usize)) {
+        Some(&count) => char::from(b'0' + heat_level(count, max) as u8),
+        None => ch,
+    });
+    Ok(heat.to_string())
+}
+
+const CELL: usize = 10;
+
+fn svg_document(grid: &Grid<char>, body: &str) -> String {
+    let (width, height) = (grid.width() * CELL, grid.height() * CELL);
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+         viewBox=\"0 0 {width} {height}\">\n\
+         <rect width=\"{width}\" height=\"{height}\" fill=\"black\"/>\n{body}</svg>\n"
+    )
+}
+
+// SVG version of `render_beams`: beams as yellow strokes, hit splitters green,
+// unhit splitters grey, mirrors white, absorbers dark red and the start as a red dot
+fn svg_beams(grid: &Grid<char>, start: (usize, usize)) -> String {
+    let (path, hit) = trace_beams(grid, start);
+    let mut body = String::new();
+
+    let mut cells: Vec<_> = path.into_iter().collect();
+    cells.sort_unstable();
+    for ((r, c), vertical) in cells {
+        let (x, y) = (c * CELL + CELL / 2, r * CELL + CELL / 2);
+        let (x1, y1, x2, y2) = if vertical {
+            (x, r * CELL, x, (r + 1) * CELL)
+        } else {
+            (c * CELL, y, (c + 1) * CELL, y)
+        };
+        body += &format!(
+            "<line x1=\"{x1}\" y1=\"{y1}\" x2=\"{x2}\" y2=\"{y2}\" stroke=\"yellow\" stroke-width=\"2\"/>\n"
+        );
+    }
+
+    for (p, &ch) in grid.iter() {
+        let (r, c) = (p.y as usize, p.x as usize);
+        let (x, y) = (c * CELL, r * CELL);
+        match ch {
+            _ if is_splitter(ch) => {
+                let fill = if hit.contains(&(r, c)) {
+                    "lime"
+                } else {
+                    "grey"
+                };
+                body += &format!(
+                    "<polygon points=\"{},{} {},{} {},{}\" fill=\"{fill}\"/>\n",
+                    x + CELL / 2,
+                    y,
+                    x,
+                    y + CELL,
+                    x + CELL,
+                    y + CELL
+                );
+            }
+            '/' | '\\' => {
+                let (y1, y2) = if ch == '/' {
+                    (y + CELL, y)
+                } else {
+                    (y, y + CELL)
+                };
+                body += &format!(
+                    "<line x1=\"{x}\" y1=\"{y1}\" x2=\"{}\" y2=\"{y2}\" stroke=\"white\" stroke-width=\"2\"/>\n",
+                    x + CELL
+                );
+            }
+            '#' => {
+                body += &format!(
+                    "<rect x=\"{x}\" y=\"{y}\" width=\"{CELL}\" height=\"{CELL}\" fill=\"darkred\"/>\n"
+                );
+            }
+            _ => {}
+        }
+    }
+
+    body += &format!(
+        "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"red\"/>\n",
+        start.1 * CELL + CELL / 2,
+        start.0 * CELL + CELL / 2,
+        CELL / 2
+    );
+
+    svg_document(grid, &body)
+}
+
+// SVG version of `render_heatmap`, with the exact count of every cell in its tooltip
+fn svg_heatmap(grid: &Grid<char>, start: (usize, usize)) -> Result<String, Cycle> {
+    let (cells, _) = timeline_counts(grid, start)?;
+    let max = cells.values().copied().max().unwrap_or(0);
+    let mut body = String::new();
+
+    let mut cells: Vec<_> = cells.into_iter().collect();
+    cells.sort_unstable();
+    for ((r, c), count) in cells {
+        let opacity = heat_level(count, max) as f64 / 9.0;
+        body += &format!(
+            "<rect x=\"{}\" y=\"{}\" width=\"{CELL}\" height=\"{CELL}\" fill=\"orange\" \
+             fill-opacity=\"{opacity:.3}\"><title>({r}, {c}): {count}</title></rect>\n",
+            c * CELL,
+            r * CELL
+        );
+    }
+
+    Ok(svg_document(grid, &body))
 }
 
 fn main() {
-    let result1 = count_splits(read_input());
+    let args = Args::from_env();
+    let svg = args.has("--svg");
+
+    if args.is_command("generate") {
+        let mut rng = Rng::new(args.number("--seed").expect("Missing --seed"));
+        let width = args.number::<usize>("--width").unwrap_or(141);
+        let height = args.number::<usize>("--height").unwrap_or(142);
+        let density = args.number("--density").unwrap_or(25);
+        print!("{}", generate::manifold(&mut rng, width, height, density));
+        return;
+    }
+
+    if args.has("--diagram") || args.has("--heatmap") {
+        let Some((grid, start)) = parse_manifold(read_input(7)) else {
+            return;
+        };
+        let heatmap = args.has("--heatmap");
+        let compact = args.has("--compact");
+        let out = match (heatmap, svg) {
+            (false, false) => Ok(render_beams(&grid, start)),
+            (false, true) => Ok(svg_beams(&grid, start)),
+            (true, false) if compact => render_heat_levels(&grid, start),
+            (true, false) => render_heatmap(&grid, start),
+            (true, true) => svg_heatmap(&grid, start),
+        };
+        match out {
+            Ok(out) => print!("{out}"),
+            Err(cycle) => eprintln!("Cannot draw heat map: {cycle}"),
+        }
+        return;
+    }
+
+    let result1 = count_splits(read_input(7));
     println!("Part 1: {result1}");
-    let result2 = count_timelines(read_input());
-    println!("Part 2: {result2}");
+    match count_timelines(read_input(7)) {
+        Ok(result2) => println!("Part 2: {result2}"),
+        Err(cycle) => println!("Part 2: infinite, {cycle}"),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc::diff::{self, Part};
+    use std::collections::BTreeSet;
     use std::io::Cursor;
 
+    // Reference for part 1 on classic manifolds: walk the beams down one row
+    // at a time, as a set of columns, counting every splitter one lands on
+    fn count_splits_naive(input: &str) -> usize {
+        let input = Input::new(input);
+        let rows: Vec<&[u8]> = input.lines().map(str::as_bytes).collect();
+        if rows.is_empty() {
+            return 0;
+        }
+        let width = rows.iter().map(|r| r.len()).max().unwrap();
+        let top = rows.iter().position(|r| r.contains(&b'S')).expect("No `S`");
+        let mut beams = BTreeSet::from([rows[top].iter().position(|&b| b == b'S').unwrap()]);
+        let mut splits = 0;
+
+        for row in &rows[top + 1..] {
+            let mut next = BTreeSet::new();
+            for &col in &beams {
+                if row.get(col) == Some(&b'^') {
+                    splits += 1;
+                    next.extend(col.checked_sub(1));
+                    next.extend((col + 1 < width).then_some(col + 1));
+                } else {
+                    next.insert(col);
+                }
+            }
+            beams = next;
+        }
+        splits
+    }
+
+    const EXAMPLE: &str = ".......S.......\n\
+                           ...............\n\
+                           .......^.......\n\
+                           ...............\n\
+                           ......^.^......\n\
+                           ...............\n\
+                           .....^.^.^.....\n\
+                           ...............\n\
+                           ....^.^...^....\n\
+                           ...............\n\
+                           ...^.^...^.^...\n\
+                           ...............\n\
+                           ..^...^.....^..\n\
+                           ...............\n\
+                           .^.^.^.^.^...^.\n\
+                           ...............";
+
     #[test]
     fn test_example() {
-        let input = ".......S.......\n\
-                     ...............\n\
-                     .......^.......\n\
-                     ...............\n\
-                     ......^.^......\n\
-                     ...............\n\
-                     .....^.^.^.....\n\
-                     ...............\n\
-                     ....^.^...^....\n\
-                     ...............\n\
-                     ...^.^...^.^...\n\
-                     ...............\n\
-                     ..^...^.....^..\n\
-                     ...............\n\
-                     .^.^.^.^.^...^.\n\
-                     ...............";
-        let reader = Cursor::new(input);
+        let reader = Cursor::new(EXAMPLE);
         assert_eq!(count_splits(reader), 21);
-        let reader = Cursor::new(input);
-        assert_eq!(count_timelines(reader), 40);
+        assert_eq!(count_splits_naive(EXAMPLE), 21);
+        let reader = Cursor::new(EXAMPLE);
+        assert_eq!(count_timelines(reader), Ok(40));
+    }
+
+    #[test]
+    fn test_render() {
+        let input = "..S..\n\
+                     .....\n\
+                     ..^..\n\
+                     .....\n\
+                     .^...\n\
+                     ....^";
+        let (grid, start) = parse_manifold(Cursor::new(input)).unwrap();
+        assert_eq!(
+            render_beams(&grid, start),
+            "..S..\n..|..\n.|^|.\n.|.|.\n|^||.\n|.||x\n"
+        );
+        assert_eq!(
+            render_heat_levels(&grid, start).unwrap(),
+            "..9..\n..9..\n.999.\n.9.9.\n9999.\n9.99^\n"
+        );
+        assert_eq!(
+            render_heatmap(&grid, start).unwrap(),
+            ". . 1 . .\n. . 1 . .\n. 1 1 1 .\n. 1 . 1 .\n1 1 1 1 .\n1 . 1 1 ^\n"
+        );
+
+        let (cells, total) = timeline_counts(&grid, start).unwrap();
+        assert_eq!(total, 3);
+        assert_eq!(cells[&(2, 2)], 1);
+        assert!(svg_beams(&grid, start).starts_with("<svg"));
+        assert!(
+            svg_heatmap(&grid, start)
+                .unwrap()
+                .contains("<title>(2, 2): 1</title>")
+        );
+
+        // Counts are printed as they are, not bucketed
+        let input = "...S...\n...^...\n..^.^..\n...^...\n.......";
+        let (grid, start) = parse_manifold(Cursor::new(input)).unwrap();
+        assert_eq!(
+            render_heatmap(&grid, start).unwrap(),
+            ". . . 1 . . .\n\
+             . . 1 1 1 . .\n\
+             . 1 1 2 1 1 .\n\
+             . 1 2 2 2 1 .\n\
+             . 1 2 . 2 1 .\n"
+        );
+    }
+
+    #[test]
+    fn test_sweep_matches_simulation() {
+        let (grid, start) = parse_manifold(Cursor::new(EXAMPLE)).unwrap();
+        assert_eq!(sweep_timelines(&grid, start), Ok(40));
+        assert_eq!(timeline_counts(&grid, start).unwrap().1, 40);
+
+        let (grid, start) = parse_manifold(Cursor::new("..S..\n.....\n..^^.\n.....")).unwrap();
+        assert_eq!(
+            sweep_timelines(&grid, start),
+            Err(Cycle(vec![(2, 2), (2, 3)]))
+        );
+        assert!(timeline_counts(&grid, start).is_err());
+    }
+
+    #[test]
+    fn test_extended_cells() {
+        // The beam turns right into a three-way splitter; one side beam is
+        // reflected into an absorber and two beams leave the manifold
+        let input = ".S..#\n\
+                     ..../\n\
+                     .\\.+\\\n\
+                     .....";
+        let (grid, start) = parse_manifold(Cursor::new(input)).unwrap();
+        assert_eq!(render_beams(&grid, start), ".S..#\n.|.-/\n.\\-+\\\n...-|\n");
+        assert_eq!(count_splits(Cursor::new(input)), 1);
+        assert_eq!(count_timelines(Cursor::new(input)), Ok(2));
+    }
+
+    #[test]
+    fn test_cycle() {
+        let input = "....\n\
+                     ./.\\\n\
+                     .S..\n\
+                     .\\./\n\
+                     ....";
+        let (grid, start) = parse_manifold(Cursor::new(input)).unwrap();
+        let simulation = simulate(&grid, start);
+        assert_eq!(
+            simulation.cycle,
+            Some(Cycle(vec![
+                (2, 1),
+                (3, 1),
+                (3, 2),
+                (3, 3),
+                (2, 3),
+                (1, 3),
+                (1, 2),
+                (1, 1)
+            ]))
+        );
+        assert!(count_timelines(Cursor::new(input)).is_err());
+        assert_eq!(count_splits(Cursor::new(input)), 0);
+    }
+
+    #[test]
+    fn test_empty() {
+        for input in ["", "\n\n", " \r\n"] {
+            assert!(parse_manifold(Cursor::new(input)).is_none());
+            assert_eq!(count_splits(Cursor::new(input)), 0);
+            assert_eq!(count_timelines(Cursor::new(input)), Ok(0));
+        }
+    }
+
+    #[test]
+    fn test_differential() {
+        let part1 = Part::new("day07 part 1", count_splits_naive, |input: &str| {
+            count_splits(Cursor::new(input))
+        });
+        // The general timeline DP against the row sweep used on classic grids
+        let part2 = Part::new(
+            "day07 part 2",
+            |input: &str| match parse_manifold(Cursor::new(input)) {
+                Some((grid, start)) => timeline_counts(&grid, start).map(|(_, total)| total),
+                None => Ok(0),
+            },
+            |input: &str| count_timelines(Cursor::new(input)),
+        );
+        let inputs = (0..30).map(|seed| generate::manifold(&mut Rng::new(seed), 31, 30, 30));
+        diff::check(&[part1, part2], inputs).unwrap_or_else(|m| panic!("{m}"));
     }
 
     #[test]
     fn test_part1() {
-        let result = count_splits(read_input());
+        let result = count_splits(read_input(7));
         assert_eq!(result, 1553);
     }
 
     #[test]
     fn test_part2() {
-        let result = count_timelines(read_input());
-        assert_eq!(result, 15811946526915);
+        let result = count_timelines(read_input(7));
+        assert_eq!(result, Ok(15811946526915));
     }
 }
@@ -0,0 +1,32 @@
+use aoc::rng::Rng;
+
+// `count` junction boxes with coordinates uniform in 0..=max
+pub fn points(rng: &mut Rng, count: usize, max: u64) -> String {
+    (0..count)
+        .map(|_| {
+            let [x, y, z] = [(); 3].map(|_| rng.between(0, max));
+            format!("{x},{y},{z}\n")
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_points;
+    use std::io::Cursor;
+
+    // Agreement with the brute force is checked by the differential test
+    #[test]
+    fn test_points() {
+        let text = points(&mut Rng::new(8), 300, 1000);
+        assert_eq!(text.lines().count(), 300);
+        let boxes = parse_points(Cursor::new(&text)).unwrap();
+        assert_eq!(boxes.len(), 300);
+        assert!(
+            boxes
+                .iter()
+                .all(|p| [p.x, p.y, p.z].iter().all(|c| (0..=1000).contains(c)))
+        );
+    }
+}
@@ -0,0 +1,247 @@
+use aoc::geometry::Point3;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+pub type Point = Point3<i64>;
+
+// Coordinates must stay below this in absolute value, so that every squared
+// distance fits in a u128
+pub const COORD_LIMIT: i64 = 1 << 62;
+
+// A pair of points (i < j) and the squared distance between them. Edges are
+// compared as plain tuples: by distance, ties broken by `i` and then `j`.
+pub type Edge = (u128, usize, usize);
+
+fn coord(p: Point, axis: usize) -> i64 {
+    match axis {
+        0 => p.x,
+        1 => p.y,
+        _ => p.z,
+    }
+}
+
+fn edge(d: u128, i: usize, j: usize) -> Edge {
+    (d, i.min(j), i.max(j))
+}
+
+// Balanced k-d tree stored implicitly: the median of every slice of `order`
+// splits it on the axis given by its depth
+pub struct KdTree<'a> {
+    points: &'a [Point],
+    order: Vec<usize>,
+}
+
+impl<'a> KdTree<'a> {
+    pub fn new(points: &'a [Point]) -> Self {
+        fn build(points: &[Point], order: &mut [usize], depth: usize) {
+            if order.len() <= 1 {
+                return;
+            }
+            let mid = order.len() / 2;
+            order.select_nth_unstable_by_key(mid, |&i| coord(points[i], depth % 3));
+            let (left, right) = order.split_at_mut(mid);
+            build(points, left, depth + 1);
+            build(points, &mut right[1..], depth + 1);
+        }
+
+        let mut order: Vec<usize> = (0..points.len()).collect();
+        build(points, &mut order, 0);
+        Self { points, order }
+    }
+
+    // The `k` points nearest to `points[query]` (excluding itself) that pass
+    // `accept`, ordered by distance and then index. Subtrees covering the
+    // `order` range `lo..hi` are not visited when `skip(lo, hi)` holds.
+    fn nearest<A, S>(&self, query: usize, k: usize, accept: &A, skip: &S) -> Vec<(u128, usize)>
+    where
+        A: Fn(usize) -> bool,
+        S: Fn(usize, usize) -> bool,
+    {
+        let mut best = BinaryHeap::new();
+        self.search(0, self.order.len(), 0, query, k, accept, skip, &mut best);
+        best.into_sorted_vec()
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn search<A, S>(
+        &self,
+        lo: usize,
+        hi: usize,
+        depth: usize,
+        query: usize,
+        k: usize,
+        accept: &A,
+        skip: &S,
+        best: &mut BinaryHeap<(u128, usize)>,
+    ) where
+        A: Fn(usize) -> bool,
+        S: Fn(usize, usize) -> bool,
+    {
+        if lo >= hi || k == 0 || skip(lo, hi) {
+            return;
+        }
+
+        let mid = lo + (hi - lo) / 2;
+        let node = self.order[mid];
+        let target = self.points[query];
+
+        if node != query && accept(node) {
+            best.push((target.distance_squared(self.points[node]), node));
+            if best.len() > k {
+                best.pop();
+            }
+        }
+
+        let axis = depth % 3;
+        let (t, c) = (coord(target, axis), coord(self.points[node], axis));
+        let (near, far) = if t < c {
+            ((lo, mid), (mid + 1, hi))
+        } else {
+            ((mid + 1, hi), (lo, mid))
+        };
+
+        self.search(near.0, near.1, depth + 1, query, k, accept, skip, best);
+        // Points at exactly the worst distance may still win on index, so only
+        // prune when the splitting plane is strictly farther away
+        if best.len() < k || (t.abs_diff(c) as u128).pow(2) <= best.peek().unwrap().0 {
+            self.search(far.0, far.1, depth + 1, query, k, accept, skip, best);
+        }
+    }
+
+    // For every subtree, identified by the position of its median in `order`,
+    // the component all of its points belong to, if they share one
+    fn uniform_components(&self, component: &[usize]) -> Vec<Option<usize>> {
+        fn fill(
+            order: &[usize],
+            component: &[usize],
+            lo: usize,
+            hi: usize,
+            uniform: &mut [Option<usize>],
+        ) -> Option<Option<usize>> {
+            if lo >= hi {
+                return None;
+            }
+            let mid = lo + (hi - lo) / 2;
+            let own = Some(component[order[mid]]);
+            let left = fill(order, component, lo, mid, uniform).unwrap_or(own);
+            let right = fill(order, component, mid + 1, hi, uniform).unwrap_or(own);
+            uniform[mid] = if left == own && right == own {
+                own
+            } else {
+                None
+            };
+            Some(uniform[mid])
+        }
+
+        let mut uniform = vec![None; self.order.len()];
+        fill(&self.order, component, 0, self.order.len(), &mut uniform);
+        uniform
+    }
+
+    // All pairs of points, lazily, in increasing order of distance
+    pub fn closest_pairs(&self) -> ClosestPairs<'_, 'a> {
+        let n = self.points.len();
+        let mut pairs = ClosestPairs {
+            tree: self,
+            neighbours: vec![Vec::new(); n],
+            cursor: vec![0; n],
+            heap: BinaryHeap::new(),
+        };
+        for i in 0..n {
+            pairs.fetch(i, 4);
+            pairs.push_next(i);
+        }
+        pairs
+    }
+}
+
+// Merges the nearest-neighbour lists of every point, each fetched from the
+// tree in growing batches, so only the pairs actually consumed are computed
+pub struct ClosestPairs<'t, 'a> {
+    tree: &'t KdTree<'a>,
+    neighbours: Vec<Vec<(u128, usize)>>,
+    cursor: Vec<usize>,
+    heap: BinaryHeap<Reverse<(Edge, usize)>>,
+}
+
+impl ClosestPairs<'_, '_> {
+    fn fetch(&mut self, i: usize, k: usize) {
+        let k = k.min(self.tree.points.len() - 1);
+        self.neighbours[i] = self.tree.nearest(i, k, &|_| true, &|_, _| false);
+    }
+
+    // Queue the next unseen neighbour of point `i`, fetching more when needed
+    fn push_next(&mut self, i: usize) {
+        let seen = self.cursor[i];
+        if seen == self.neighbours[i].len() && seen < self.tree.points.len() - 1 {
+            self.fetch(i, 2 * seen.max(1));
+        }
+        if let Some(&(d, j)) = self.neighbours[i].get(seen) {
+            self.heap.push(Reverse((edge(d, i, j), i)));
+        }
+    }
+}
+
+impl Iterator for ClosestPairs<'_, '_> {
+    type Item = Edge;
+
+    fn next(&mut self) -> Option<Edge> {
+        // Every pair is queued once from each end; keep the copy from its lower end
+        while let Some(Reverse((pair, from))) = self.heap.pop() {
+            self.cursor[from] += 1;
+            self.push_next(from);
+            if from == pair.1 {
+                return Some(pair);
+            }
+        }
+        None
+    }
+}
+
+// Euclidean minimum spanning tree built with Borůvka's algorithm: every round,
+// each component is joined to its nearest other component, found with the
+// k-d tree while skipping subtrees that lie entirely inside the component
+pub fn minimum_spanning_tree(tree: &KdTree) -> Vec<Edge> {
+    fn root(parent: &mut [usize], mut x: usize) -> usize {
+        while parent[x] != x {
+            parent[x] = parent[parent[x]];
+            x = parent[x];
+        }
+        x
+    }
+
+    let n = tree.points.len();
+    let mut component: Vec<usize> = (0..n).collect();
+    let mut parent: Vec<usize> = (0..n).collect();
+    let mut edges = Vec::new();
+
+    while edges.len() + 1 < n {
+        let uniform = tree.uniform_components(&component);
+        let mut best: Vec<Option<Edge>> = vec![None; n];
+
+        for p in 0..n {
+            let c = component[p];
+            let accept = |q: usize| component[q] != c;
+            let skip = |lo: usize, hi: usize| uniform[lo + (hi - lo) / 2] == Some(c);
+            if let Some(&(d, q)) = tree.nearest(p, 1, &accept, &skip).first() {
+                let candidate = edge(d, p, q);
+                if best[c].is_none_or(|b| candidate < b) {
+                    best[c] = Some(candidate);
+                }
+            }
+        }
+
+        for (d, i, j) in best.into_iter().flatten() {
+            let (a, b) = (root(&mut parent, i), root(&mut parent, j));
+            if a != b {
+                parent[a] = b;
+                edges.push((d, i, j));
+            }
+        }
+        for (p, c) in component.iter_mut().enumerate() {
+            *c = root(&mut parent, p);
+        }
+    }
+
+    edges
+}
@@ -1,163 +1,341 @@
-use std::collections::HashMap;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+mod generate;
+mod kdtree;
+mod report;
 
-struct UnionFind {
-    parent: Vec<usize>,
-    size: Vec<usize>,
-}
+use aoc::args::Args;
+use aoc::input::{Input, read_input};
+use aoc::rng::Rng;
+use aoc::union_find::UnionFind;
+use kdtree::{KdTree, Point};
+use std::fmt;
+use std::io::BufRead;
 
-impl UnionFind {
-    fn new(n: usize) -> Self {
-        Self {
-            parent: (0..n).collect(),
-            size: vec![1; n],
-        }
-    }
-
-    fn find(&mut self, x: usize) -> usize {
-        if self.parent[x] != x {
-            self.parent[x] = self.find(self.parent[x]);
-        }
-        self.parent[x]
-    }
-
-    fn union(&mut self, x: usize, y: usize) {
-        let (px, py) = (self.find(x), self.find(y));
-        if px != py {
-            let (small, large) = if self.size[px] < self.size[py] {
-                (px, py)
-            } else {
-                (py, px)
-            };
-            self.parent[small] = large;
-            self.size[large] += self.size[small];
-        }
+// Fewer than two boxes make no circuits worth multiplying, so give 0
+fn solve_part1<R: BufRead>(reader: R, connections: usize) -> Result<usize, OutOfRange> {
+    let points = parse_points(reader)?;
+    if points.len() < 2 {
+        return Ok(0);
     }
-
-    fn top3_sizes(&mut self) -> usize {
-        let mut sizes: HashMap<usize, usize> = HashMap::new();
-        for i in 0..self.parent.len() {
-            *sizes.entry(self.find(i)).or_insert(0) += 1;
-        }
-        let mut v: Vec<_> = sizes.values().copied().collect();
-        v.sort_unstable_by(|a, b| b.cmp(a));
-        v.iter().take(3).product()
+    let tree = KdTree::new(&points);
+    let mut uf = UnionFind::new(points.len());
+    for (_, i, j) in tree.closest_pairs().take(connections) {
+        uf.union(i, j);
     }
 
-    fn num_components(&mut self) -> usize {
-        let mut roots = std::collections::HashSet::new();
-        for i in 0..self.parent.len() {
-            roots.insert(self.find(i));
-        }
-        roots.len()
-    }
+    Ok(uf.largest_k(3).iter().product())
 }
 
-fn solve_part1<R: BufRead>(reader: R, connections: usize) -> usize {
-    let (points, edges) = parse_and_sort(reader);
-    let mut uf = UnionFind::new(points.len());
-    edges
-        .iter()
-        .take(connections)
-        .for_each(|(_, i, j)| uf.union(*i, *j));
+// Connecting boxes closest-first, the connection that finally joins everything
+// into one circuit is the longest edge of the minimum spanning tree. The X
+// coordinates may be negative and up to 2^62, so their product is an i128.
+fn solve_part2<R: BufRead>(reader: R) -> Result<i128, OutOfRange> {
+    let points = parse_points(reader)?;
+    let tree = KdTree::new(&points);
+    // Fewer than two boxes need no connection at all
+    let Some((_, i, j)) = kdtree::minimum_spanning_tree(&tree).into_iter().max() else {
+        return Ok(0);
+    };
 
-    uf.top3_sizes()
+    Ok(points[i].x as i128 * points[j].x as i128)
 }
 
-fn solve_part2<R: BufRead>(reader: R) -> usize {
-    let (points, edges) = parse_and_sort(reader);
-    let mut uf = UnionFind::new(points.len());
-    let mut last_connection = (0, 0);
+type Points = Vec<Point>;
 
-    for (_, i, j) in edges {
-        if uf.find(i) != uf.find(j) {
-            uf.union(i, j);
-            last_connection = (i, j);
-            if uf.num_components() == 1 {
-                break;
-            }
-        }
-    }
-
-    points[last_connection.0].0 as usize * points[last_connection.1].0 as usize
+// A coordinate too far out for the k-d tree's exact distances
+#[derive(Debug, PartialEq, Eq)]
+struct OutOfRange {
+    line: usize,
+    coordinate: i64,
 }
 
-type Points = Vec<(i32, i32, i32)>;
-type Edges = Vec<(f64, usize, usize)>;
+impl fmt::Display for OutOfRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}: coordinate {} is out of range, expected less than {} from 0",
+            self.line,
+            self.coordinate,
+            kdtree::COORD_LIMIT
+        )
+    }
+}
 
-fn parse_and_sort<R: BufRead>(reader: R) -> (Points, Edges) {
-    let points: Points = reader
+fn parse_points<R: BufRead>(reader: R) -> Result<Points, OutOfRange> {
+    Input::from_reader(reader)
         .lines()
-        .map(|l| {
-            let v: Vec<i32> = l.unwrap().split(',').map(|s| s.parse().unwrap()).collect();
-            (v[0], v[1], v[2])
+        .enumerate()
+        .filter(|(_, l)| !l.is_empty())
+        .map(|(i, l)| {
+            let line = i + 1;
+            let v: Vec<i64> = l.split(',').map(|s| s.parse().unwrap()).collect();
+            // unsigned_abs, as abs() overflows on i64::MIN
+            if let Some(&coordinate) = v
+                .iter()
+                .find(|c| c.unsigned_abs() >= kdtree::COORD_LIMIT as u64)
+            {
+                return Err(OutOfRange { line, coordinate });
+            }
+            Ok(Point::new(v[0], v[1], v[2]))
         })
-        .collect();
+        .collect()
+}
+
+// Brute-force reference: every pair of points, sorted by distance and then (i, j)
+#[cfg(test)]
+fn parse_and_sort<R: BufRead>(reader: R) -> Result<(Points, Vec<kdtree::Edge>), OutOfRange> {
+    let points = parse_points(reader)?;
 
     let n = points.len();
     let mut edges = Vec::new();
     for i in 0..n {
         for j in i + 1..n {
-            let (x1, y1, z1) = points[i];
-            let (x2, y2, z2) = points[j];
-            let dx = (x2 - x1) as f64;
-            let dy = (y2 - y1) as f64;
-            let dz = (z2 - z1) as f64;
-            let dist = (dx * dx + dy * dy + dz * dz).sqrt();
-            edges.push((dist, i, j));
+            edges.push((points[i].distance_squared(points[j]), i, j));
         }
     }
 
-    edges.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
-    (points, edges)
+    edges.sort_unstable();
+    Ok((points, edges))
 }
 
-fn read_input() -> BufReader<File> {
-    BufReader::new(File::open("../inputs/day08.txt").expect("Failed to open input file"))
+fn fail(error: &OutOfRange) -> ! {
+    eprintln!("Invalid input: {error}");
+    std::process::exit(1);
 }
 
 fn main() {
-    println!("Part 1: {}", solve_part1(read_input(), 1000));
-    println!("Part 2: {}", solve_part2(read_input()));
+    let args = Args::from_env();
+
+    if args.is_command("generate") {
+        let mut rng = Rng::new(args.number("--seed").expect("Missing --seed"));
+        let count = args.number::<usize>("--count").unwrap_or(1000);
+        let max = args.number("--max").unwrap_or(100_000);
+        print!("{}", generate::points(&mut rng, count, max));
+        return;
+    }
+
+    if let Some(format) = args.value("--report") {
+        let connections = args.number("--connections").unwrap_or(1000);
+        let points = parse_points(read_input(8)).unwrap_or_else(|e| fail(&e));
+        let report = report::Report::new(points, connections);
+        match format {
+            "json" => print!("{}", report.to_json()),
+            "dot" => print!("{}", report.to_dot()),
+            _ => eprintln!("Unknown report format {format:?}, expected json or dot"),
+        }
+        return;
+    }
+
+    let result1 = solve_part1(read_input(8), 1000).unwrap_or_else(|e| fail(&e));
+    println!("Part 1: {result1}");
+    let result2 = solve_part2(read_input(8)).unwrap_or_else(|e| fail(&e));
+    println!("Part 2: {result2}");
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc::diff::{self, Part};
     use std::io::Cursor;
 
+    const EXAMPLE: &str = "162,817,812\n\
+                           57,618,57\n\
+                           906,360,560\n\
+                           592,479,940\n\
+                           352,342,300\n\
+                           466,668,158\n\
+                           542,29,236\n\
+                           431,825,988\n\
+                           739,650,466\n\
+                           52,470,668\n\
+                           216,146,977\n\
+                           819,987,18\n\
+                           117,168,530\n\
+                           805,96,715\n\
+                           346,949,466\n\
+                           970,615,88\n\
+                           941,993,340\n\
+                           862,61,35\n\
+                           984,92,344\n\
+                           425,690,689";
+
     #[test]
     fn test_example() {
-        let input = "162,817,812\n\
-                     57,618,57\n\
-                     906,360,560\n\
-                     592,479,940\n\
-                     352,342,300\n\
-                     466,668,158\n\
-                     542,29,236\n\
-                     431,825,988\n\
-                     739,650,466\n\
-                     52,470,668\n\
-                     216,146,977\n\
-                     819,987,18\n\
-                     117,168,530\n\
-                     805,96,715\n\
-                     346,949,466\n\
-                     970,615,88\n\
-                     941,993,340\n\
-                     862,61,35\n\
-                     984,92,344\n\
-                     425,690,689";
-        let reader = Cursor::new(input);
-        assert_eq!(solve_part1(reader, 10), 40);
-        let reader = Cursor::new(input);
-        assert_eq!(solve_part2(reader), 25272);
+        let reader = Cursor::new(EXAMPLE);
+        assert_eq!(solve_part1(reader, 10), Ok(40));
+        let reader = Cursor::new(EXAMPLE);
+        assert_eq!(solve_part2(reader), Ok(25272));
+    }
+
+    #[test]
+    fn test_report() {
+        let report = report::Report::new(parse_points(Cursor::new(EXAMPLE)).unwrap(), 10);
+        let sizes: Vec<_> = report.circuits.iter().map(Vec::len).collect();
+        assert_eq!(sizes[..4], [5, 4, 2, 2]);
+        assert_eq!(sizes.len(), 11);
+
+        assert_eq!(report.tree.len(), 19);
+        let last = report.merges.last().unwrap();
+        assert_eq!((last.edge.1, last.edge.2), (10, 12));
+        assert_eq!((last.left, last.right, last.size), (10, 37, 20));
+
+        let json = report.to_json();
+        assert!(json.contains("\"boxes\": [{\"index\": 0, \"x\": 162, \"y\": 817, \"z\": 812}"));
+        assert!(json.contains("{\"cluster\": 38, \"left\": 10, \"right\": 37, \"size\": 20"));
+        let dot = report.to_dot();
+        assert!(dot.starts_with("graph day08 {"));
+        assert!(dot.contains("10 -- 12 [label=\"#18: "));
+    }
+
+    fn brute_force_part1(input: &str, connections: usize) -> Result<usize, OutOfRange> {
+        let (points, edges) = parse_and_sort(Cursor::new(input))?;
+        if points.len() < 2 {
+            return Ok(0);
+        }
+        let mut uf = UnionFind::new(points.len());
+        for (_, i, j) in edges.into_iter().take(connections) {
+            uf.union(i, j);
+        }
+        Ok(uf.largest_k(3).iter().product())
+    }
+
+    fn brute_force_part2(input: &str) -> Result<i128, OutOfRange> {
+        let (points, edges) = parse_and_sort(Cursor::new(input))?;
+        let mut uf = UnionFind::new(points.len());
+        let mut last_connection = None;
+
+        for (_, i, j) in edges {
+            if uf.union(i, j) {
+                last_connection = Some((i, j));
+                if uf.components() == 1 {
+                    break;
+                }
+            }
+        }
+
+        Ok(last_connection.map_or(0, |(i, j)| points[i].x as i128 * points[j].x as i128))
+    }
+
+    #[test]
+    fn test_differential() {
+        let parts = [
+            Part::new(
+                "day08 part 1",
+                |input: &str| brute_force_part1(input, 100),
+                |input: &str| solve_part1(Cursor::new(input), 100),
+            ),
+            Part::new("day08 part 2", brute_force_part2, |input: &str| {
+                solve_part2(Cursor::new(input))
+            }),
+        ];
+        // Small coordinates make for plenty of equally long connections
+        let inputs = (0..10).map(|seed| generate::points(&mut Rng::new(seed), 200, 50));
+        diff::check(&parts, inputs).unwrap_or_else(|m| panic!("{m}"));
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        // A lattice has many equal distances, exercising the tie-breaking order
+        let mut input = String::new();
+        for i in 0..150u32 {
+            let (x, y, z) = (i * 37 % 11, i * 53 % 7, i * 17 % 5);
+            input += &format!("{},{},{}\n", x * 10, y * 10 + i % 3, z * 10);
+        }
+        let input = input.trim_end();
+
+        let (points, edges) = parse_and_sort(Cursor::new(input)).unwrap();
+        let tree = KdTree::new(&points);
+        assert!(tree.closest_pairs().eq(edges.iter().copied()));
+
+        for connections in [0, 1, 10, 100, 1000] {
+            assert_eq!(
+                solve_part1(Cursor::new(input), connections),
+                brute_force_part1(input, connections)
+            );
+        }
+        assert_eq!(solve_part2(Cursor::new(input)), brute_force_part2(input));
+    }
+
+    #[test]
+    fn test_exact_distances() {
+        // Differences of a few units this far out are lost in an f64
+        let input = "2305843009213693952,0,0\n\
+                     2305843009213693954,0,0\n\
+                     2305843009213693955,0,0\n\
+                     -2305843009213693952,0,0";
+        let points = parse_points(Cursor::new(input)).unwrap();
+        let tree = KdTree::new(&points);
+        let pairs: Vec<_> = tree.closest_pairs().take(3).collect();
+        assert_eq!(pairs, vec![(1, 1, 2), (4, 0, 1), (9, 0, 2)]);
+
+        // Equal distances are ordered by (i, j)
+        let points = parse_points(Cursor::new("0,0,0\n1,0,0\n2,0,0")).unwrap();
+        let tree = KdTree::new(&points);
+        let pairs: Vec<_> = tree.closest_pairs().collect();
+        assert_eq!(pairs, vec![(1, 0, 1), (1, 1, 2), (4, 0, 2)]);
+    }
+
+    #[test]
+    fn test_out_of_range() {
+        let input = "0,0,0\n1,-9223372036854775808,0";
+        let error = parse_points(Cursor::new(input)).unwrap_err();
+        assert_eq!(
+            error,
+            OutOfRange {
+                line: 2,
+                coordinate: i64::MIN
+            }
+        );
+        assert_eq!(
+            solve_part2(Cursor::new("4611686018427387904,0,0")),
+            Err(OutOfRange {
+                line: 1,
+                coordinate: 1 << 62
+            })
+        );
+        assert!(parse_points(Cursor::new("4611686018427387903,0,0")).is_ok());
+
+        // Blank lines still count towards the line number
+        let input = "0,0,0\n\n1,2,-4611686018427387905";
+        assert_eq!(
+            parse_points(Cursor::new(input)),
+            Err(OutOfRange {
+                line: 3,
+                coordinate: -4611686018427387905
+            })
+        );
+    }
+
+    #[test]
+    fn test_too_few_boxes() {
+        for input in ["", "\r\n\r\n", "1,2,3"] {
+            assert_eq!(solve_part1(Cursor::new(input), 10), Ok(0));
+            assert_eq!(solve_part2(Cursor::new(input)), Ok(0));
+            assert_eq!(brute_force_part1(input, 10), Ok(0));
+            assert_eq!(brute_force_part2(input), Ok(0));
+        }
+    }
+
+    #[test]
+    fn test_large_and_negative_x() {
+        let input = "1099511627776,0,0
+1099511627777,0,0";
+        assert_eq!(solve_part2(Cursor::new(input)), Ok((1 << 80) + (1 << 40)));
+        assert_eq!(solve_part2(Cursor::new(input)), brute_force_part2(input));
+
+        let input = "-3,0,0
+5,0,0";
+        assert_eq!(solve_part2(Cursor::new(input)), Ok(-15));
+        assert_eq!(solve_part2(Cursor::new(input)), brute_force_part2(input));
+
+        let input = "-4611686018427387903,0,0
+4611686018427387903,1,0";
+        let x = (1i128 << 62) - 1;
+        assert_eq!(solve_part2(Cursor::new(input)), Ok(-x * x));
     }
 
     #[test]
     fn test_part1() {
-        let result = solve_part1(read_input(), 1000);
-        assert_eq!(result, 131150);
+        let result = solve_part1(read_input(8), 1000);
+        assert_eq!(result, Ok(131150));
     }
 }
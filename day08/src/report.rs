@@ -0,0 +1,159 @@
+use crate::Points;
+use crate::kdtree::{self, Edge, KdTree};
+use aoc::geometry::Point3;
+use aoc::union_find::UnionFind;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+// One step of single-linkage clustering. Clusters are numbered like a SciPy
+// linkage matrix: boxes are clusters 0..n, and step k creates cluster n + k.
+pub struct Merge {
+    pub edge: Edge,
+    pub left: usize,
+    pub right: usize,
+    pub size: usize,
+}
+
+pub struct Report {
+    pub points: Points,
+    pub connections: usize,
+    // Circuits after the first `connections` connections, largest first
+    pub circuits: Vec<Vec<usize>>,
+    // Minimum spanning tree, shortest edge first
+    pub tree: Vec<Edge>,
+    pub merges: Vec<Merge>,
+}
+
+impl Report {
+    pub fn new(points: Points, connections: usize) -> Self {
+        let n = points.len();
+        let tree = KdTree::new(&points);
+
+        let mut uf = UnionFind::new(n);
+        for (_, i, j) in tree.closest_pairs().take(connections) {
+            uf.union(i, j);
+        }
+        let mut members: HashMap<usize, Vec<usize>> = HashMap::new();
+        for i in 0..n {
+            members.entry(uf.find(i)).or_default().push(i);
+        }
+        let mut circuits: Vec<_> = members.into_values().collect();
+        circuits.sort_unstable_by(|a, b| b.len().cmp(&a.len()).then(a[0].cmp(&b[0])));
+
+        let mut mst = kdtree::minimum_spanning_tree(&tree);
+        mst.sort_unstable();
+
+        // Replaying the spanning tree edges in order is Kruskal's algorithm,
+        // so it merges components exactly as connecting closest-first does
+        let mut uf = UnionFind::new(n);
+        let mut cluster: Vec<usize> = (0..n).collect();
+        let merges = mst
+            .iter()
+            .enumerate()
+            .map(|(step, &edge)| {
+                let (left, right) = (cluster[uf.find(edge.1)], cluster[uf.find(edge.2)]);
+                uf.union(edge.1, edge.2);
+                let root = uf.find(edge.1);
+                cluster[root] = n + step;
+                Merge {
+                    edge,
+                    left,
+                    right,
+                    size: uf.size_of(root),
+                }
+            })
+            .collect();
+
+        Self {
+            points,
+            connections,
+            circuits,
+            tree: mst,
+            merges,
+        }
+    }
+
+    fn json_box(&self, i: usize) -> String {
+        let Point3 { x, y, z } = self.points[i];
+        format!("{{\"index\": {i}, \"x\": {x}, \"y\": {y}, \"z\": {z}}}")
+    }
+
+    pub fn to_json(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "{{").unwrap();
+        writeln!(out, "  \"connections\": {},", self.connections).unwrap();
+
+        writeln!(out, "  \"circuits\": [").unwrap();
+        for (k, circuit) in self.circuits.iter().enumerate() {
+            let boxes: Vec<_> = circuit.iter().map(|&i| self.json_box(i)).collect();
+            let sep = if k + 1 < self.circuits.len() { "," } else { "" };
+            writeln!(
+                out,
+                "    {{\"size\": {}, \"boxes\": [{}]}}{sep}",
+                circuit.len(),
+                boxes.join(", ")
+            )
+            .unwrap();
+        }
+        writeln!(out, "  ],").unwrap();
+
+        writeln!(out, "  \"spanning_tree\": [").unwrap();
+        for (k, &(d, i, j)) in self.tree.iter().enumerate() {
+            let sep = if k + 1 < self.tree.len() { "," } else { "" };
+            writeln!(
+                out,
+                "    {{\"from\": {i}, \"to\": {j}, \"distance_squared\": {d}}}{sep}"
+            )
+            .unwrap();
+        }
+        writeln!(out, "  ],").unwrap();
+
+        writeln!(out, "  \"merges\": [").unwrap();
+        let n = self.points.len();
+        for (k, m) in self.merges.iter().enumerate() {
+            let sep = if k + 1 < self.merges.len() { "," } else { "" };
+            writeln!(
+                out,
+                "    {{\"cluster\": {}, \"left\": {}, \"right\": {}, \"size\": {}, \
+                 \"from\": {}, \"to\": {}, \"distance_squared\": {}}}{sep}",
+                n + k,
+                m.left,
+                m.right,
+                m.size,
+                m.edge.1,
+                m.edge.2,
+                m.edge.0
+            )
+            .unwrap();
+        }
+        writeln!(out, "  ]").unwrap();
+        writeln!(out, "}}").unwrap();
+        out
+    }
+
+    // Graphviz graph of the spanning tree, with each circuit drawn as a
+    // cluster and every edge labelled with its merge step and squared length
+    pub fn to_dot(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "graph day08 {{").unwrap();
+        writeln!(out, "  node [shape=box];").unwrap();
+
+        for (k, circuit) in self.circuits.iter().enumerate() {
+            writeln!(out, "  subgraph cluster_{k} {{").unwrap();
+            writeln!(out, "    label=\"circuit {k} ({} boxes)\";", circuit.len()).unwrap();
+            for &i in circuit {
+                let Point3 { x, y, z } = self.points[i];
+                writeln!(out, "    {i} [label=\"{i}: {x},{y},{z}\"];").unwrap();
+            }
+            writeln!(out, "  }}").unwrap();
+        }
+
+        for (step, m) in self.merges.iter().enumerate() {
+            let (d, i, j) = m.edge;
+            writeln!(out, "  {i} -- {j} [label=\"#{step}: {d}\"];").unwrap();
+        }
+
+        writeln!(out, "}}").unwrap();
+        out
+    }
+}
@@ -0,0 +1,114 @@
+use crate::Point;
+use std::collections::VecDeque;
+
+// A rectilinear polygon on a coordinate-compressed grid. Every distinct vertex
+// coordinate gets its own row/column of cells, the tiles strictly between two
+// consecutive coordinates are collapsed into one cell, and a ring of padding
+// cells surrounds everything so the outside is connected.
+pub struct CompressedPolygon {
+    // Compressed cell of every vertex, in input order
+    vertex_cells: Vec<(usize, usize)>,
+    width: usize,
+    // prefix[y * (width + 1) + x] counts the tile-holding cells outside the
+    // polygon in the compressed rectangle [0, x) x [0, y)
+    prefix: Vec<u32>,
+}
+
+// Sorted distinct coordinates, and for each compressed index whether its
+// cell holds any tiles: vertex lines always do, gaps only if non-empty
+fn compress(values: impl Iterator<Item = i64>) -> (Vec<i64>, Vec<bool>) {
+    let mut coords: Vec<i64> = values.collect();
+    coords.sort_unstable();
+    coords.dedup();
+
+    let mut has_tiles = vec![false; 2 * coords.len() + 1];
+    for (k, pair) in coords.windows(2).enumerate() {
+        has_tiles[2 * k + 1] = true;
+        has_tiles[2 * k + 2] = pair[1] - pair[0] > 1;
+    }
+    if !coords.is_empty() {
+        has_tiles[2 * coords.len() - 1] = true;
+    }
+
+    (coords, has_tiles)
+}
+
+fn index_of(coords: &[i64], value: i64) -> usize {
+    2 * coords.binary_search(&value).unwrap() + 1
+}
+
+impl CompressedPolygon {
+    pub fn new(points: &[Point]) -> Self {
+        let (xs, x_tiles) = compress(points.iter().map(|p| p.x));
+        let (ys, y_tiles) = compress(points.iter().map(|p| p.y));
+        let (width, height) = (x_tiles.len(), y_tiles.len());
+
+        let vertex_cells: Vec<_> = points
+            .iter()
+            .map(|p| (index_of(&xs, p.x), index_of(&ys, p.y)))
+            .collect();
+
+        // Walk every edge to mark the boundary
+        let mut boundary = vec![false; width * height];
+        for (k, &(x1, y1)) in vertex_cells.iter().enumerate() {
+            let (x2, y2) = vertex_cells[(k + 1) % vertex_cells.len()];
+            for y in y1.min(y2)..=y1.max(y2) {
+                for x in x1.min(x2)..=x1.max(x2) {
+                    boundary[y * width + x] = true;
+                }
+            }
+        }
+
+        // Flood the outside from the padding corner
+        let mut outside = vec![false; width * height];
+        let mut queue = VecDeque::from([(0usize, 0usize)]);
+        outside[0] = true;
+        while let Some((x, y)) = queue.pop_front() {
+            let neighbours = [
+                (x.wrapping_sub(1), y),
+                (x + 1, y),
+                (x, y.wrapping_sub(1)),
+                (x, y + 1),
+            ];
+            for (nx, ny) in neighbours {
+                if nx < width && ny < height {
+                    let cell = ny * width + nx;
+                    if !boundary[cell] && !outside[cell] {
+                        outside[cell] = true;
+                        queue.push_back((nx, ny));
+                    }
+                }
+            }
+        }
+
+        let stride = width + 1;
+        let mut prefix = vec![0u32; stride * (height + 1)];
+        for y in 0..height {
+            for x in 0..width {
+                let bad = outside[y * width + x] && x_tiles[x] && y_tiles[y];
+                prefix[(y + 1) * stride + x + 1] =
+                    bad as u32 + prefix[y * stride + x + 1] + prefix[(y + 1) * stride + x]
+                        - prefix[y * stride + x];
+            }
+        }
+
+        Self {
+            vertex_cells,
+            width,
+            prefix,
+        }
+    }
+
+    // Whether every tile of the rectangle spanned by vertices `i` and `j` lies
+    // inside or on the polygon, in constant time
+    pub fn contains_rectangle(&self, i: usize, j: usize) -> bool {
+        let (xi, yi) = self.vertex_cells[i];
+        let (xj, yj) = self.vertex_cells[j];
+        let (x_lo, x_hi) = (xi.min(xj), xi.max(xj) + 1);
+        let (y_lo, y_hi) = (yi.min(yj), yi.max(yj) + 1);
+
+        let stride = self.width + 1;
+        let at = |x: usize, y: usize| self.prefix[y * stride + x];
+        at(x_hi, y_hi) + at(x_lo, y_lo) == at(x_lo, y_hi) + at(x_hi, y_lo)
+    }
+}
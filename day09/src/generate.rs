@@ -0,0 +1,84 @@
+use aoc::rng::Rng;
+
+// A simple rectilinear polygon of 4 * columns red tiles with coordinates in
+// 0..=max. It is a row of `columns` vertical slabs, each spanning a random
+// height range that overlaps its neighbour's, traced along the tops left to
+// right and back along the bottoms.
+pub fn polygon(rng: &mut Rng, columns: usize, max: u64) -> String {
+    assert!(
+        columns >= 1 && max as usize > columns + 2,
+        "Not enough room"
+    );
+
+    // Distinct slab boundaries
+    let mut xs = Vec::new();
+    while xs.len() < columns + 1 {
+        let x = rng.between(0, max);
+        if !xs.contains(&x) {
+            xs.push(x);
+        }
+    }
+    xs.sort_unstable();
+
+    // Neighbouring slabs must share some height, and differ in both top and
+    // bottom, or their corners would coincide or their sides touch. A thin
+    // slab against the floor or ceiling has no such neighbour, so after
+    // enough misses the previous slab is drawn again.
+    let mut slabs: Vec<(u64, u64)> = Vec::new();
+    let mut misses = 0;
+    while slabs.len() < columns {
+        let bottom = rng.between(0, max - 1);
+        let top = rng.between(bottom + 1, max);
+        let fits = slabs
+            .last()
+            .is_none_or(|&(b, t)| b != bottom && t != top && b.max(bottom) < t.min(top));
+        if fits {
+            slabs.push((bottom, top));
+            misses = 0;
+        } else if misses == 100 {
+            slabs.pop();
+            misses = 0;
+        } else {
+            misses += 1;
+        }
+    }
+
+    let mut points: Vec<(u64, u64)> = Vec::new();
+    for (k, &(_, top)) in slabs.iter().enumerate() {
+        points.push((xs[k], top));
+        points.push((xs[k + 1], top));
+    }
+    for (k, &(bottom, _)) in slabs.iter().enumerate().rev() {
+        points.push((xs[k + 1], bottom));
+        points.push((xs[k], bottom));
+    }
+
+    points.iter().map(|(x, y)| format!("{x},{y}\n")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_numbered_points;
+    use crate::validate::validate;
+    use std::io::Cursor;
+
+    // Agreement with the tile-by-tile brute force is checked by the
+    // differential test
+    #[test]
+    fn test_polygon() {
+        let mut rng = Rng::new(9);
+        for columns in [1, 2, 5, 12] {
+            let text = polygon(&mut rng, columns, 40);
+            let points = parse_numbered_points(Cursor::new(&text));
+            assert_eq!(points.len(), 4 * columns);
+            assert!(
+                points
+                    .iter()
+                    .all(|(_, p)| (0..=40).contains(&p.x) && (0..=40).contains(&p.y)),
+                "{text}"
+            );
+            assert!(validate(&points).is_ok(), "{text}");
+        }
+    }
+}
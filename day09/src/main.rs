@@ -1,54 +1,108 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+mod compressed;
+mod generate;
+mod svg;
+mod validate;
 
-fn parse_points<R: BufRead>(reader: R) -> Vec<(i64, i64)> {
-    reader
+use aoc::args::Args;
+use aoc::geometry::{Point2, Rect};
+use aoc::input::{Input, read_input};
+use aoc::rng::Rng;
+use compressed::CompressedPolygon;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::io::BufRead;
+use validate::NumberedPoint;
+
+type Point = Point2<i64>;
+
+// Parse the red tiles, remembering the line each one came from
+fn parse_numbered_points<R: BufRead>(reader: R) -> Vec<NumberedPoint> {
+    Input::from_reader(reader)
         .lines()
-        .map(|l| {
-            let parts: Vec<i64> = l.unwrap().split(',').map(|s| s.parse().unwrap()).collect();
-            (parts[0], parts[1])
+        .enumerate()
+        .filter(|(_, l)| !l.is_empty())
+        .map(|(i, l)| {
+            let parts: Vec<i64> = l.split(',').map(|s| s.parse().unwrap()).collect();
+            (i + 1, Point::new(parts[0], parts[1]))
         })
         .collect()
 }
 
-fn solve_part1<R: BufRead>(reader: R) -> i64 {
-    let points = parse_points(reader);
-    let mut max_area = 0;
+fn parse_points<R: BufRead>(reader: R) -> Vec<Point> {
+    parse_numbered_points(reader)
+        .into_iter()
+        .map(|(_, point)| point)
+        .collect()
+}
+
+// A rectangle given by the indices of the two red tiles at opposite corners,
+// with `corner_a < corner_b`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Rectangle {
+    corner_a: usize,
+    corner_b: usize,
+    area: i64,
+}
+
+// Tiles covered by the rectangle with red tiles `a` and `b` at opposite corners
+fn area(a: Point, b: Point) -> i64 {
+    Rect::from_corners(a, b).lattice_points() as i64
+}
+
+// The `n` largest rectangles with red tiles as opposite corners that pass
+// `allowed`, largest first and ties by corner indices
+fn largest_rectangles<F>(points: &[Point], n: usize, allowed: F) -> Vec<Rectangle>
+where
+    F: Fn(usize, usize) -> bool,
+{
+    let mut best = BinaryHeap::new();
 
     for i in 0..points.len() {
         for j in i + 1..points.len() {
-            let (x1, y1) = points[i];
-            let (x2, y2) = points[j];
-            max_area = max_area.max(((x1 - x2).abs() + 1) * ((y1 - y2).abs() + 1));
+            if allowed(i, j) {
+                best.push(Reverse((area(points[i], points[j]), Reverse((i, j)))));
+                if best.len() > n {
+                    best.pop();
+                }
+            }
         }
     }
-    max_area
+
+    best.into_sorted_vec()
+        .into_iter()
+        .map(|Reverse((area, Reverse((i, j))))| Rectangle {
+            corner_a: i,
+            corner_b: j,
+            area,
+        })
+        .collect()
+}
+
+// The largest rectangle, or None with fewer than two red tiles
+fn solve_part1<R: BufRead>(reader: R) -> Option<Rectangle> {
+    let points = parse_points(reader);
+    largest_rectangles(&points, 1, |_, _| true).first().copied()
 }
 
 // Check if point is inside or on the boundary of a polygon
-fn is_inside_or_on_polygon(point: (i64, i64), polygon: &[(i64, i64)]) -> bool {
-    let (x, y) = point;
-
-    // Check if point is a vertex or lies on any axis-aligned edge
-    for i in 0..polygon.len() {
-        let (x1, y1) = polygon[i];
-        let (x2, y2) = polygon[(i + 1) % polygon.len()];
-
-        if (x, y) == (x1, y1)
-            || (x >= x1.min(x2)
-                && x <= x1.max(x2)
-                && y >= y1.min(y2)
-                && y <= y1.max(y2)
-                && ((x1 == x2 && x == x1) || (y1 == y2 && y == y1)))
-        {
-            return true;
-        }
+#[cfg(test)]
+fn is_inside_or_on_polygon(point: Point, polygon: &[Point]) -> bool {
+    use aoc::geometry::Segment;
+
+    let edge = |i: usize| Segment::new(polygon[i], polygon[(i + 1) % polygon.len()]);
+
+    // Check if point lies on any edge, corners included
+    if (0..polygon.len()).any(|i| edge(i).contains(point)) {
+        return true;
     }
 
     // Ray casting algorithm: count edge crossings from point to infinity
+    let Point { x, y } = point;
     (0..polygon.len()).fold(false, |inside, i| {
-        let (x1, y1) = polygon[i];
-        let (x2, y2) = polygon[(i + 1) % polygon.len()];
+        let Segment {
+            a: Point { x: x1, y: y1 },
+            b: Point { x: x2, y: y2 },
+        } = edge(i);
         if ((y1 > y) != (y2 > y)) && (x < (x2 - x1) * (y - y1) / (y2 - y1) + x1) {
             !inside
         } else {
@@ -57,91 +111,294 @@ fn is_inside_or_on_polygon(point: (i64, i64), polygon: &[(i64, i64)]) -> bool {
     })
 }
 
-// Check if two axis-aligned segments cross perpendicularly (not parallel or touching)
-fn crosses(rect: ((i64, i64), (i64, i64)), poly: ((i64, i64), (i64, i64))) -> bool {
-    let ((x1, y1), (x2, y2)) = rect;
-    let ((x3, y3), (x4, y4)) = poly;
-
-    (y1 == y2
-        && x3 == x4
-        && x3 > x1.min(x2)
-        && x3 < x1.max(x2)
-        && y1 > y3.min(y4)
-        && y1 < y3.max(y4))
-        || (x1 == x2
-            && y3 == y4
-            && x1 > x3.min(x4)
-            && x1 < x3.max(x4)
-            && y3 > y1.min(y2)
-            && y3 < y1.max(y2))
+// The largest rectangle inside the polygon, or None if no rectangle fits
+fn solve_part2<R: BufRead>(reader: R) -> Option<Rectangle> {
+    let points = parse_points(reader);
+    let polygon = CompressedPolygon::new(&points);
+    largest_rectangles(&points, 1, |i, j| polygon.contains_rectangle(i, j))
+        .first()
+        .copied()
 }
 
-fn solve_part2<R: BufRead>(reader: R) -> i64 {
-    let points = parse_points(reader);
-    let mut max_area = 0;
+// One line per rectangle: rank, area and both corners with their input lines
+fn describe(points: &[NumberedPoint], rectangles: &[Rectangle]) -> String {
+    let corner = |k: usize| {
+        let (line, p) = points[k];
+        format!("({},{}) on line {line}", p.x, p.y)
+    };
+    rectangles
+        .iter()
+        .enumerate()
+        .map(|(rank, r)| {
+            format!(
+                "{:>3}. area {}: {} to {}\n",
+                rank + 1,
+                r.area,
+                corner(r.corner_a),
+                corner(r.corner_b)
+            )
+        })
+        .collect()
+}
 
-    for i in 0..points.len() {
-        for j in i + 1..points.len() {
-            let (x_lo, x_hi) = (points[i].0.min(points[j].0), points[i].0.max(points[j].0));
-            let (y_lo, y_hi) = (points[i].1.min(points[j].1), points[i].1.max(points[j].1));
+// Picture of the floor with the best part 1 rectangle in blue and the best
+// part 2 rectangle in orange, plus the runners-up of part 2 in grey
+fn render_svg(points: &[Point], top: usize) -> String {
+    let polygon = CompressedPolygon::new(points);
+    let part1 = largest_rectangles(points, 1, |_, _| true);
+    let part2 = largest_rectangles(points, top.max(1), |i, j| polygon.contains_rectangle(i, j));
 
-            let corners = [(x_lo, y_lo), (x_hi, y_lo), (x_hi, y_hi), (x_lo, y_hi)];
-            if !corners.iter().all(|&c| is_inside_or_on_polygon(c, &points)) {
-                continue;
-            }
+    let candidate = |r: &Rectangle, colour, label| svg::Highlight {
+        corners: (r.corner_a, r.corner_b),
+        colour,
+        label,
+    };
+    let mut highlights: Vec<_> = part2
+        .iter()
+        .skip(1)
+        .take(top.saturating_sub(1))
+        .rev()
+        .map(|r| candidate(r, "grey", false))
+        .collect();
+    highlights.extend(part1.first().map(|r| candidate(r, "blue", true)));
+    highlights.extend(part2.first().map(|r| candidate(r, "orange", true)));
 
-            let has_crossing = [
-                ((x_lo, y_lo), (x_hi, y_lo)),
-                ((x_hi, y_lo), (x_hi, y_hi)),
-                ((x_hi, y_hi), (x_lo, y_hi)),
-                ((x_lo, y_hi), (x_lo, y_lo)),
-            ]
-            .iter()
-            .any(|&re| {
-                (0..points.len()).any(|k| crosses(re, (points[k], points[(k + 1) % points.len()])))
-            });
+    svg::render(points, &highlights)
+}
+
+fn main() {
+    let args = Args::from_env();
+
+    if args.is_command("generate") {
+        let mut rng = Rng::new(args.number("--seed").expect("Missing --seed"));
+        let columns = args.number::<usize>("--columns").unwrap_or(124);
+        let max = args.number("--max").unwrap_or(100_000);
+        print!("{}", generate::polygon(&mut rng, columns, max));
+        return;
+    }
 
-            if !has_crossing {
-                max_area = max_area.max((x_hi - x_lo + 1) * (y_hi - y_lo + 1));
+    match validate::validate(&parse_numbered_points(read_input(9))) {
+        Ok(winding) => eprintln!("Polygon is valid, wound {winding}"),
+        Err(problems) => {
+            for problem in problems {
+                eprintln!("{problem}");
             }
+            std::process::exit(1);
         }
     }
-    max_area
-}
 
-fn read_input() -> BufReader<File> {
-    BufReader::new(File::open("../inputs/day09.txt").expect("Failed to open input file"))
-}
+    let top: Option<usize> = args.number("--top");
+    if args.has("--svg") {
+        print!(
+            "{}",
+            render_svg(&parse_points(read_input(9)), top.unwrap_or(1))
+        );
+        return;
+    }
+    if let Some(n) = top {
+        let numbered = parse_numbered_points(read_input(9));
+        let points: Vec<Point> = numbered.iter().map(|&(_, point)| point).collect();
+        let polygon = CompressedPolygon::new(&points);
+        println!("Part 1:");
+        print!(
+            "{}",
+            describe(&numbered, &largest_rectangles(&points, n, |_, _| true))
+        );
+        println!("Part 2:");
+        let inside = largest_rectangles(&points, n, |i, j| polygon.contains_rectangle(i, j));
+        print!("{}", describe(&numbered, &inside));
+        return;
+    }
 
-fn main() {
-    println!("Part 1: {}", solve_part1(read_input()));
-    println!("Part 2: {}", solve_part2(read_input()));
+    println!(
+        "Part 1: {}",
+        solve_part1(read_input(9)).map_or(0, |r| r.area)
+    );
+    println!(
+        "Part 2: {}",
+        solve_part2(read_input(9)).map_or(0, |r| r.area)
+    );
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc::diff::{self, Part};
     use std::io::Cursor;
 
+    const EXAMPLE: &str = "7,1\n\
+                           11,1\n\
+                           11,7\n\
+                           9,7\n\
+                           9,5\n\
+                           2,5\n\
+                           2,3\n\
+                           7,3";
+
     #[test]
     fn test_example() {
-        let input = "7,1\n\
-                     11,1\n\
-                     11,7\n\
-                     9,7\n\
-                     9,5\n\
-                     2,5\n\
-                     2,3\n\
-                     7,3";
-        let reader = Cursor::new(input);
-        assert_eq!(solve_part1(reader), 50);
-        let reader = Cursor::new(input);
-        assert_eq!(solve_part2(reader), 24);
+        let reader = Cursor::new(EXAMPLE);
+        assert_eq!(solve_part1(reader).unwrap().area, 50);
+        let reader = Cursor::new(format!("{EXAMPLE}\n\n"));
+        assert_eq!(solve_part1(reader).unwrap().area, 50);
+        let reader = Cursor::new(EXAMPLE);
+        assert_eq!(
+            solve_part2(reader),
+            Some(Rectangle {
+                corner_a: 4,
+                corner_b: 6,
+                area: 24
+            })
+        );
+        assert_eq!(solve_part2_tiles(EXAMPLE), 24);
+    }
+
+    // Reference solution testing every tile of every rectangle
+    fn solve_part2_tiles(input: &str) -> i64 {
+        let points = parse_points(Cursor::new(input));
+        let mut max_area = 0;
+
+        for (i, &a) in points.iter().enumerate() {
+            for &b in &points[i + 1..] {
+                let r = Rect::from_corners(a, b);
+                let inside = (r.min.x..=r.max.x).all(|x| {
+                    (r.min.y..=r.max.y).all(|y| is_inside_or_on_polygon(Point::new(x, y), &points))
+                });
+                if inside {
+                    max_area = max_area.max(area(a, b));
+                }
+            }
+        }
+        max_area
+    }
+
+    #[test]
+    fn test_too_few_points() {
+        assert_eq!(solve_part1(Cursor::new("")), None);
+        assert_eq!(solve_part2(Cursor::new("")), None);
+        assert_eq!(solve_part1(Cursor::new("3,4")), None);
+        assert_eq!(solve_part2(Cursor::new("3,4")), None);
+        assert_eq!(solve_part2_tiles("3,4"), 0);
+    }
+
+    #[test]
+    fn test_matches_tiles() {
+        // A skyline: columns of varying height standing on a common base. The
+        // notch at x = 9..10 lies entirely inside the otherwise best rectangle
+        // (3, 9)-(14, 1) without crossing its edges.
+        let heights = [
+            (0, 5),
+            (3, 9),
+            (4, 2),
+            (9, 7),
+            (10, 1),
+            (14, 12),
+            (16, 4),
+            (20, 8),
+        ];
+        let end = 25;
+        let mut points = vec![(end, 0), (0, 0)];
+        for (k, &(x, h)) in heights.iter().enumerate() {
+            points.push((x, h));
+            let next = heights.get(k + 1).map_or(end, |p| p.0);
+            points.push((next, h));
+        }
+        let input: Vec<_> = points.iter().map(|(x, y)| format!("{x},{y}")).collect();
+        let input = input.join("\n");
+
+        assert_eq!(solve_part2(Cursor::new(&input)).unwrap().area, 54);
+        assert_eq!(solve_part2_tiles(&input), 54);
+    }
+
+    #[test]
+    fn test_largest_rectangles() {
+        let points = parse_points(Cursor::new(EXAMPLE));
+        let polygon = CompressedPolygon::new(&points);
+        let best = largest_rectangles(&points, 3, |i, j| polygon.contains_rectangle(i, j));
+        let found: Vec<_> = best
+            .iter()
+            .map(|r| (r.area, r.corner_a, r.corner_b))
+            .collect();
+        assert_eq!(found, vec![(24, 4, 6), (21, 1, 3), (18, 5, 7)]);
+        let numbered = parse_numbered_points(Cursor::new(EXAMPLE));
+        assert_eq!(
+            describe(&numbered, &best[..2]),
+            "  1. area 24: (9,5) on line 5 to (2,3) on line 7\n  \
+             2. area 21: (11,1) on line 2 to (9,7) on line 4\n"
+        );
+        // Corners keep their input lines past a blank line
+        let spaced = EXAMPLE.replacen('\n', "\n\n", 2);
+        let numbered = parse_numbered_points(Cursor::new(&spaced));
+        assert_eq!(
+            describe(&numbered, &best[..1]),
+            "  1. area 24: (9,5) on line 7 to (2,3) on line 9\n"
+        );
+
+        let svg = render_svg(&points, 3);
+        assert!(svg.starts_with("<svg"));
+        assert_eq!(svg.matches("stroke=\"grey\"").count(), 2);
+        assert!(svg.contains(">2,3</text>"));
+        assert!(svg.contains(">9,5</text>"));
+    }
+
+    #[test]
+    fn test_validate() {
+        use validate::{Problem, Winding};
+
+        let points = parse_numbered_points(Cursor::new(EXAMPLE));
+        assert_eq!(validate::validate(&points), Ok(Winding::CounterClockwise));
+        let reversed: Vec<_> = points.iter().rev().copied().collect();
+        assert_eq!(validate::validate(&reversed), Ok(Winding::Clockwise));
+
+        // A repeated vertex, then diagonal edges back to the start
+        let input = "0,0\n4,0\n4,4\n0,4\n4,0\n6,2";
+        let points = parse_numbered_points(Cursor::new(input));
+        assert_eq!(
+            validate::validate(&points),
+            Err(vec![
+                Problem::DuplicateVertex(2, 5),
+                Problem::DiagonalEdge(4, 5),
+                Problem::DiagonalEdge(5, 6),
+                Problem::DiagonalEdge(6, 1),
+            ])
+        );
+
+        // A figure eight
+        let input = "0,0\n4,0\n4,4\n2,4\n2,-2\n0,-2";
+        let points = parse_numbered_points(Cursor::new(input));
+        assert_eq!(
+            validate::validate(&points),
+            Err(vec![Problem::SelfIntersection((1, 2), (4, 5))])
+        );
+
+        // An edge doubling back over its neighbour
+        let input = "0,0\n6,0\n3,0\n3,4\n0,4";
+        let points = parse_numbered_points(Cursor::new(input));
+        assert_eq!(
+            validate::validate(&points),
+            Err(vec![
+                Problem::SelfIntersection((1, 2), (2, 3)),
+                Problem::SelfIntersection((1, 2), (3, 4)),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_differential() {
+        // Dropping a corner leaves no polygon, so shrinking keeps to valid ones
+        let valid =
+            |input: &str| validate::validate(&parse_numbered_points(Cursor::new(input))).is_ok();
+        let part2 = Part::new("day09 part 2", solve_part2_tiles, |input: &str| {
+            solve_part2(Cursor::new(input)).map_or(0, |r| r.area)
+        })
+        .valid_when(valid);
+        let inputs = (0..20).map(|seed| generate::polygon(&mut Rng::new(seed), 6, 30));
+        diff::check(&[part2], inputs).unwrap_or_else(|m| panic!("{m}"));
     }
 
     #[test]
     fn test_part2() {
-        let result = solve_part2(read_input());
-        assert_eq!(result, 1644094530);
+        let result = solve_part2(read_input(9));
+        assert_eq!(result.unwrap().area, 1644094530);
     }
 }
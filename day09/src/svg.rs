@@ -0,0 +1,89 @@
+use crate::Point;
+use aoc::geometry::Rect;
+use std::fmt::Write;
+
+// A rectangle to draw, given by the indices of its two corner tiles
+pub struct Highlight<'a> {
+    pub corners: (usize, usize),
+    pub colour: &'a str,
+    pub label: bool,
+}
+
+// Draw the floor: the polygon and its interior in green, red tiles as red
+// squares, and each highlighted rectangle outlined on top, later highlights
+// over earlier ones. Tiles are unit squares centred on their coordinates.
+pub fn render(points: &[Point], highlights: &[Highlight]) -> String {
+    let x_min = points.iter().map(|p| p.x).min().unwrap_or(0);
+    let x_max = points.iter().map(|p| p.x).max().unwrap_or(0);
+    let y_min = points.iter().map(|p| p.y).min().unwrap_or(0);
+    let y_max = points.iter().map(|p| p.y).max().unwrap_or(0);
+    let extent = (x_max - x_min).max(y_max - y_min).max(1) as f64;
+    let margin = extent / 20.0;
+    let font = extent / 60.0;
+
+    let mut out = String::new();
+    writeln!(
+        out,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"1000\" height=\"1000\" \
+         viewBox=\"{} {} {} {}\">",
+        x_min as f64 - margin,
+        y_min as f64 - margin,
+        (x_max - x_min) as f64 + 2.0 * margin,
+        (y_max - y_min) as f64 + 2.0 * margin
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "<rect x=\"-100%\" y=\"-100%\" width=\"300%\" height=\"300%\" fill=\"white\"/>"
+    )
+    .unwrap();
+
+    // A one-tile-wide stroke along the boundary covers exactly the tiles on it
+    let outline: Vec<_> = points.iter().map(|p| format!("{},{}", p.x, p.y)).collect();
+    writeln!(
+        out,
+        "<polygon points=\"{}\" fill=\"limegreen\" stroke=\"limegreen\" stroke-width=\"1\"/>",
+        outline.join(" ")
+    )
+    .unwrap();
+
+    for Point { x, y } in points {
+        writeln!(
+            out,
+            "<rect x=\"{}\" y=\"{}\" width=\"1\" height=\"1\" fill=\"red\"/>",
+            *x as f64 - 0.5,
+            *y as f64 - 0.5
+        )
+        .unwrap();
+    }
+
+    for highlight in highlights {
+        let (a, b) = (points[highlight.corners.0], points[highlight.corners.1]);
+        let r = Rect::from_corners(a, b);
+        writeln!(
+            out,
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"none\" stroke=\"{}\" \
+             stroke-width=\"2\" vector-effect=\"non-scaling-stroke\"/>",
+            r.min.x as f64 - 0.5,
+            r.min.y as f64 - 0.5,
+            r.width() + 1,
+            r.height() + 1,
+            highlight.colour
+        )
+        .unwrap();
+
+        if highlight.label {
+            for Point { x, y } in [a, b] {
+                writeln!(
+                    out,
+                    "<text x=\"{x}\" y=\"{y}\" font-size=\"{font}\" fill=\"{}\">{x},{y}</text>",
+                    highlight.colour
+                )
+                .unwrap();
+            }
+        }
+    }
+
+    writeln!(out, "</svg>").unwrap();
+    out
+}
@@ -0,0 +1,134 @@
+use crate::Point;
+use aoc::geometry::Segment;
+use std::collections::HashMap;
+use std::fmt;
+
+// A vertex together with the input line it came from
+pub type NumberedPoint = (usize, Point);
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Problem {
+    TooFewVertices(usize),
+    // Edge between the vertices on these two lines is neither horizontal nor vertical
+    DiagonalEdge(usize, usize),
+    // Vertex on the second line repeats the one on the first
+    DuplicateVertex(usize, usize),
+    // Two edges, each given by the lines of its end vertices, touch or cross
+    SelfIntersection((usize, usize), (usize, usize)),
+    ZeroArea,
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Problem::TooFewVertices(n) => write!(f, "only {n} vertices, need at least 4"),
+            Problem::DiagonalEdge(a, b) => {
+                write!(f, "line {a}: edge to line {b} is not axis-aligned")
+            }
+            Problem::DuplicateVertex(first, again) => {
+                write!(f, "line {again}: duplicates the vertex on line {first}")
+            }
+            Problem::SelfIntersection((a1, a2), (b1, b2)) => {
+                write!(f, "lines {a1}-{a2}: edge meets the edge on lines {b1}-{b2}")
+            }
+            Problem::ZeroArea => write!(f, "polygon encloses no area"),
+        }
+    }
+}
+
+// Orientation of the vertex order, with y pointing up
+#[derive(Debug, PartialEq, Eq)]
+pub enum Winding {
+    Clockwise,
+    CounterClockwise,
+}
+
+impl fmt::Display for Winding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Winding::Clockwise => write!(f, "clockwise"),
+            Winding::CounterClockwise => write!(f, "counter-clockwise"),
+        }
+    }
+}
+
+// Check that the vertices form a closed, simple, rectilinear polygon, and find
+// its winding. Every problem found is reported, in input order.
+pub fn validate(points: &[NumberedPoint]) -> Result<Winding, Vec<Problem>> {
+    let n = points.len();
+    if n < 4 {
+        return Err(vec![Problem::TooFewVertices(n)]);
+    }
+
+    let mut problems = Vec::new();
+    let edge = |k: usize| (points[k], points[(k + 1) % n]);
+    let is_straight = |k: usize| {
+        let ((_, a), (_, b)) = edge(k);
+        Segment::new(a, b).is_axis_aligned()
+    };
+
+    let mut seen = HashMap::new();
+    for &(line, point) in points {
+        if let Some(&first) = seen.get(&point) {
+            problems.push(Problem::DuplicateVertex(first, line));
+        } else {
+            seen.insert(point, line);
+        }
+    }
+
+    for k in 0..n {
+        if !is_straight(k) {
+            let ((a, _), (b, _)) = edge(k);
+            problems.push(Problem::DiagonalEdge(a, b));
+        }
+    }
+
+    for i in 0..n {
+        for j in i + 1..n {
+            if !is_straight(i) || !is_straight(j) {
+                continue;
+            }
+            let ((a1, p1), (a2, p2)) = edge(i);
+            let ((b1, q1), (b2, q2)) = edge(j);
+
+            let meet = if j == i + 1 || (i == 0 && j == n - 1) {
+                // Neighbouring edges share a vertex, and only overlap further
+                // when the second one doubles back along the first
+                let (shared, from, to) = if j == i + 1 {
+                    (p2, p1, q2)
+                } else {
+                    (p1, p2, q1)
+                };
+                let back = |a: i64, b: i64, c: i64| (a - b).signum() == (c - b).signum();
+                Segment::new(from, to).is_axis_aligned()
+                    && back(from.x, shared.x, to.x)
+                    && back(from.y, shared.y, to.y)
+            } else {
+                Segment::new(p1, p2).intersects(&Segment::new(q1, q2))
+            };
+
+            if meet {
+                problems.push(Problem::SelfIntersection((a1, a2), (b1, b2)));
+            }
+        }
+    }
+
+    let twice_area: i128 = (0..n)
+        .map(|k| {
+            let ((_, a), (_, b)) = edge(k);
+            a.cross(b)
+        })
+        .sum();
+    if twice_area == 0 && problems.is_empty() {
+        problems.push(Problem::ZeroArea);
+    }
+
+    if !problems.is_empty() {
+        return Err(problems);
+    }
+    Ok(if twice_area > 0 {
+        Winding::CounterClockwise
+    } else {
+        Winding::Clockwise
+    })
+}
@@ -0,0 +1,70 @@
+use aoc::rng::Rng;
+
+// `count` machines of up to `max_lights` lights and `max_buttons` buttons,
+// each button wired to a random non-empty set of lights. The light pattern is
+// what some random presses produce, so every machine can be solved, and the
+// joltage list counts how often each light was toggled.
+pub fn machines(rng: &mut Rng, count: usize, max_lights: usize, max_buttons: usize) -> String {
+    assert!(max_lights >= 1 && (1..64).contains(&max_buttons));
+    let mut out = String::new();
+
+    for _ in 0..count {
+        let lights = rng.between(1, max_lights as u64) as usize;
+        let buttons: Vec<Vec<usize>> = (0..rng.between(1, max_buttons as u64))
+            .map(|_| {
+                let wired: Vec<usize> = (0..lights).filter(|_| rng.chance(1, 3)).collect();
+                if wired.is_empty() {
+                    vec![rng.below(lights as u64) as usize]
+                } else {
+                    wired
+                }
+            })
+            .collect();
+
+        let mut joltage = vec![0; lights];
+        for button in &buttons {
+            let presses = rng.below(4);
+            for &light in button {
+                joltage[light] += presses;
+            }
+        }
+
+        let pattern: String = joltage
+            .iter()
+            .map(|j| if j % 2 == 1 { '#' } else { '.' })
+            .collect();
+        let wiring: Vec<String> = buttons
+            .iter()
+            .map(|b| {
+                let lights: Vec<_> = b.iter().map(|l| l.to_string()).collect();
+                format!("({})", lights.join(","))
+            })
+            .collect();
+        let joltage: Vec<_> = joltage.iter().map(|j| j.to_string()).collect();
+        out += &format!(
+            "[{pattern}] {} {{{}}}\n",
+            wiring.join(" "),
+            joltage.join(",")
+        );
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_line, solve_machine};
+
+    #[test]
+    fn test_machines() {
+        let text = machines(&mut Rng::new(10), 100, 8, 10);
+        assert_eq!(text.lines().count(), 100);
+
+        // Every machine can be solved, pressing each button at most once
+        for line in text.lines() {
+            let (target, buttons) = parse_line(line);
+            assert!(solve_machine(&target, &buttons) <= buttons.len(), "{line}");
+        }
+    }
+}
@@ -1,5 +1,9 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+mod generate;
+
+use aoc::args::Args;
+use aoc::input::{Input, read_input};
+use aoc::rng::Rng;
+use std::io::BufRead;
 
 fn parse_line(line: &str) -> (Vec<bool>, Vec<Vec<usize>>) {
     let target_start = line.find('[').unwrap() + 1;
@@ -114,29 +118,34 @@ fn solve_machine(target: &[bool], buttons: &[Vec<usize>]) -> usize {
 }
 
 fn solve<R: BufRead>(reader: R) -> usize {
-    reader
-        .lines()
-        .filter_map(|line| {
-            let line = line.unwrap();
-            (!line.trim().is_empty()).then(|| {
-                let (target, buttons) = parse_line(&line);
-                solve_machine(&target, &buttons)
-            })
+    Input::from_reader(reader)
+        .non_empty_lines()
+        .map(|line| {
+            let (target, buttons) = parse_line(line);
+            solve_machine(&target, &buttons)
         })
         .sum()
 }
 
-fn read_input() -> BufReader<File> {
-    BufReader::new(File::open("../inputs/day10.txt").expect("Failed to open input file"))
-}
-
 fn main() {
-    println!("Part 1: {}", solve(read_input()));
+    let args = Args::from_env();
+
+    if args.is_command("generate") {
+        let mut rng = Rng::new(args.number("--seed").expect("Missing --seed"));
+        let count = args.number::<usize>("--count").unwrap_or(200);
+        let lights = args.number::<usize>("--lights").unwrap_or(10);
+        let buttons = args.number::<usize>("--buttons").unwrap_or(13);
+        print!("{}", generate::machines(&mut rng, count, lights, buttons));
+        return;
+    }
+
+    println!("Part 1: {}", solve(read_input(10)));
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc::diff::{self, Part};
     use std::io::Cursor;
 
     #[test]
@@ -148,8 +157,40 @@ mod tests {
         assert_eq!(solve(reader), 7);
     }
 
+    // Reference: try every subset of buttons on every machine
+    fn solve_brute_force(input: &str) -> usize {
+        input
+            .lines()
+            .map(|line| {
+                let (target, buttons) = parse_line(line);
+                (0..1u64 << buttons.len())
+                    .filter(|mask| {
+                        let mut lights = vec![false; target.len()];
+                        for (b, button) in buttons.iter().enumerate() {
+                            if mask & (1 << b) != 0 {
+                                button.iter().for_each(|&l| lights[l] ^= true);
+                            }
+                        }
+                        lights == target
+                    })
+                    .map(|mask| mask.count_ones() as usize)
+                    .min()
+                    .unwrap_or(usize::MAX)
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_differential() {
+        let part1 = Part::new("day10 part 1", solve_brute_force, |input: &str| {
+            solve(Cursor::new(input))
+        });
+        let inputs = (0..20).map(|seed| generate::machines(&mut Rng::new(seed), 30, 8, 10));
+        diff::check(&[part1], inputs).unwrap_or_else(|m| panic!("{m}"));
+    }
+
     #[test]
     fn test_part1() {
-        assert_eq!(solve(read_input()), 571);
+        assert_eq!(solve(read_input(10)), 571);
     }
 }
@@ -0,0 +1,68 @@
+use aoc::rng::Rng;
+
+// A device graph without cycles: `nodes` devices in a random order, each but
+// the last wired to 1..=degree devices at most `reach` places after it. The
+// puzzle's devices are all present: `svr` first, `out` last, and `you`, `fft`
+// and `dac` somewhere in between.
+pub fn dag(rng: &mut Rng, nodes: usize, degree: usize, reach: usize) -> String {
+    let named = ["svr", "you", "fft", "dac", "out"];
+    assert!(nodes >= named.len() && degree >= 1 && reach >= 1);
+
+    let mut names: Vec<String> = Vec::new();
+    while names.len() < nodes - named.len() {
+        let name: String = (0..3)
+            .map(|_| char::from(b'a' + rng.below(26) as u8))
+            .collect();
+        if !named.contains(&name.as_str()) && !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    for name in &named[1..4] {
+        let at = rng.between(0, names.len() as u64) as usize;
+        names.insert(at, name.to_string());
+    }
+    names.insert(0, "svr".to_string());
+    names.push("out".to_string());
+
+    let mut out = String::new();
+    for (k, name) in names.iter().enumerate().take(nodes - 1) {
+        let last = (k + reach).min(nodes - 1);
+        let mut targets: Vec<usize> = Vec::new();
+        for _ in 0..degree.min(last - k) {
+            let t = rng.between((k + 1) as u64, last as u64) as usize;
+            if !targets.contains(&t) {
+                targets.push(t);
+            }
+        }
+        let targets: Vec<&str> = targets.iter().map(|&t| names[t].as_str()).collect();
+        out += &format!("{name}: {}\n", targets.join(" "));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Options, parse_input, query};
+    use std::io::Cursor;
+
+    #[test]
+    fn test_dag() {
+        let text = dag(&mut Rng::new(11), 60, 3, 8);
+        let graph = parse_input(Cursor::new(&text));
+        assert_eq!(graph.len(), 60);
+        assert_eq!(text.lines().count(), 59);
+        let svr = graph.id("svr").unwrap();
+        assert!(graph.topological_order(svr).is_ok());
+
+        let simple = Options {
+            simple: true,
+            modulus: None,
+        };
+        for (source, via) in [("svr", &["fft", "dac"][..]), ("you", &[])] {
+            let dp = query(&graph, source, "out", via, &[], Options::default());
+            assert_eq!(dp, query(&graph, source, "out", via, &[], simple));
+        }
+        assert!(query(&graph, "svr", "out", &[], &[], simple).unwrap() > 0);
+    }
+}
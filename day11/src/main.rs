@@ -1,158 +1,360 @@
-use std::collections::{HashMap, HashSet};
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-
-fn parse_input<R: BufRead>(reader: R) -> HashMap<String, Vec<String>> {
-    let mut graph = HashMap::new();
-
-    for line in reader.lines() {
-        let line = line.unwrap();
-        if line.trim().is_empty() {
-            continue;
+mod generate;
+
+use aoc::args::Args;
+use aoc::graph::{Cycle, Graph, NodeId};
+use aoc::input::{Input, read_input};
+use aoc::rng::Rng;
+use std::fmt;
+use std::io::BufRead;
+
+fn parse_input<R: BufRead>(reader: R) -> Graph {
+    let mut graph = Graph::new();
+
+    for line in Input::from_reader(reader).non_empty_lines() {
+        let (node, outputs) = line.split_once(": ").unwrap();
+        graph.intern(node);
+        for output in outputs.split_whitespace() {
+            graph.add_edge(node, output);
         }
-
-        let parts: Vec<&str> = line.split(": ").collect();
-        let node = parts[0].to_string();
-        let outputs: Vec<String> = parts[1].split_whitespace().map(|s| s.to_string()).collect();
-
-        graph.insert(node, outputs);
     }
 
     graph
 }
 
-fn count_paths(
-    graph: &HashMap<String, Vec<String>>,
-    current: &str,
-    target: &str,
-    visited: &mut HashSet<String>,
-) -> usize {
-    if current == target {
-        return 1;
+// Upper bound on the nodes `count_simple_paths` may enter. Enumerating simple
+// paths is exponential in general, so the search gives up rather than hang.
+const SIMPLE_PATH_STEPS: usize = 100_000_000;
+
+#[derive(Debug, PartialEq, Eq)]
+enum PathError {
+    // Names along a cycle reachable from the source, which makes the number
+    // of paths infinite
+    Cycle(Vec<String>),
+    // The simple path search took more steps than its limit
+    TooManySteps(usize),
+    UnknownDevice(String),
+    // An exact count does not fit in a u128
+    Overflow,
+}
+
+impl fmt::Display for PathError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PathError::Cycle(names) => {
+                write!(
+                    f,
+                    "device graph has a cycle: {} -> {}",
+                    names.join(" -> "),
+                    names[0]
+                )
+            }
+            PathError::TooManySteps(limit) => {
+                write!(f, "gave up counting simple paths after {limit} steps")
+            }
+            PathError::UnknownDevice(name) => write!(f, "no device named {name}"),
+            PathError::Overflow => write!(f, "path count overflows u128, try --modulo"),
+        }
     }
+}
 
-    visited.insert(current.to_string());
+// How paths are counted: `simple` uses the bounded simple path search, which
+// copes with cycles, and a `modulus` reduces every count modulo it
+#[derive(Clone, Copy, Default)]
+struct Options {
+    simple: bool,
+    modulus: Option<u64>,
+}
 
-    let mut total_paths = 0;
+impl Options {
+    fn reduce(self, n: u128) -> u128 {
+        self.modulus.map_or(n, |m| n % m as u128)
+    }
 
-    if let Some(neighbors) = graph.get(current) {
-        for neighbor in neighbors {
-            if !visited.contains(neighbor) {
-                total_paths += count_paths(graph, neighbor, target, visited);
-            }
-        }
+    // Both operands are already reduced, so below 2^64 when there is a
+    // modulus, and only exact counts can overflow
+    fn add(self, a: u128, b: u128) -> Result<u128, PathError> {
+        let sum = a.checked_add(b).ok_or(PathError::Overflow)?;
+        Ok(self.reduce(sum))
     }
 
-    visited.remove(current);
-    total_paths
+    fn mul(self, a: u128, b: u128) -> Result<u128, PathError> {
+        let product = a.checked_mul(b).ok_or(PathError::Overflow)?;
+        Ok(self.reduce(product))
+    }
 }
 
-// Topological sort using DFS
-fn toposort(
-    graph: &HashMap<String, Vec<String>>,
-    node: &str,
-    visited: &mut HashSet<String>,
-    stack: &mut Vec<String>,
-) {
-    if visited.contains(node) {
-        return;
+// Count paths from `source` to `target` that repeat no node, pass every node
+// of `via` and none of `avoid`, by depth-first search. Well-defined on graphs
+// with cycles, but exponential, so at most `limit` nodes are entered. The
+// search keeps its own stack, so long paths cannot overflow the thread's.
+fn count_simple_paths(
+    graph: &Graph,
+    source: NodeId,
+    target: NodeId,
+    via: &[NodeId],
+    avoid: &[NodeId],
+    limit: usize,
+) -> Result<usize, PathError> {
+    // Avoided nodes look already visited, so the search never enters them
+    let mut visited = vec![false; graph.len()];
+    for &node in avoid {
+        visited[node as usize] = true;
     }
-    visited.insert(node.to_string());
+    if [source, target]
+        .iter()
+        .chain(via)
+        .any(|&n| visited[n as usize])
+    {
+        return Ok(0);
+    }
+
+    let mut steps = limit;
+    let mut enter = || {
+        steps = steps.checked_sub(1).ok_or(PathError::TooManySteps(limit))?;
+        Ok(())
+    };
+    // Reaching the target ends a path, which counts if it met every waypoint
+    let complete = |visited: &[bool]| via.iter().all(|&v| visited[v as usize] || v == target);
 
-    if let Some(neighbors) = graph.get(node) {
-        for neighbor in neighbors {
-            toposort(graph, neighbor, visited, stack);
+    enter()?;
+    if source == target {
+        return Ok(complete(&visited) as usize);
+    }
+    let mut paths = 0;
+    let mut stack = vec![(source, 0)];
+    visited[source as usize] = true;
+
+    while let Some((node, next)) = stack.last_mut() {
+        let node = *node;
+        match graph.neighbours(node).get(*next) {
+            Some(&child) => {
+                *next += 1;
+                if visited[child as usize] {
+                    continue;
+                }
+                enter()?;
+                if child == target {
+                    paths += complete(&visited) as usize;
+                } else {
+                    visited[child as usize] = true;
+                    stack.push((child, 0));
+                }
+            }
+            None => {
+                visited[node as usize] = false;
+                stack.pop();
+            }
         }
     }
 
-    stack.push(node.to_string());
+    Ok(paths)
 }
 
-// Count paths from source to all nodes using DP with topological sort
-fn count_paths_from(graph: &HashMap<String, Vec<String>>, source: &str) -> HashMap<String, usize> {
-    let mut visited = HashSet::new();
-    let mut stack = Vec::new();
+// Nodes reachable from `source` in topological order, or the cycle that
+// makes some path counts infinite
+fn topological_order(graph: &Graph, source: NodeId) -> Result<Vec<NodeId>, PathError> {
+    graph.topological_order(source).map_err(|Cycle(nodes)| {
+        PathError::Cycle(nodes.iter().map(|&n| graph.name(n).to_string()).collect())
+    })
+}
 
-    // Get all nodes reachable from source
-    toposort(graph, source, &mut visited, &mut stack);
-    stack.reverse(); // Process in topological order
+// Count paths from source to all nodes using DP with topological sort. Fails
+// if a cycle is reachable, as some counts would then be infinite.
+fn count_paths_from(
+    graph: &Graph,
+    source: NodeId,
+    options: Options,
+) -> Result<Vec<u128>, PathError> {
+    let order = topological_order(graph, source)?;
 
-    let mut counts = HashMap::new();
-    counts.insert(source.to_string(), 1);
+    let mut counts = vec![0; graph.len()];
+    counts[source as usize] = options.reduce(1);
 
-    for node in stack {
-        let current_count = *counts.get(&node).unwrap_or(&0);
+    for node in order {
+        let current_count = counts[node as usize];
         if current_count == 0 {
             continue;
         }
 
-        if let Some(neighbors) = graph.get(&node) {
-            for neighbor in neighbors {
-                *counts.entry(neighbor.clone()).or_insert(0) += current_count;
-            }
+        for &neighbor in graph.neighbours(node) {
+            let count = &mut counts[neighbor as usize];
+            *count = options.add(*count, current_count)?;
         }
     }
 
-    counts
+    Ok(counts)
 }
 
-// Build reverse graph
-fn reverse_graph(graph: &HashMap<String, Vec<String>>) -> HashMap<String, Vec<String>> {
-    let mut rev = HashMap::new();
+fn resolve(graph: &Graph, names: &[&str]) -> Result<Vec<NodeId>, PathError> {
+    names
+        .iter()
+        .map(|&name| {
+            graph
+                .id(name)
+                .ok_or(PathError::UnknownDevice(name.to_string()))
+        })
+        .collect()
+}
 
-    for (node, neighbors) in graph {
-        rev.entry(node.clone()).or_insert_with(Vec::new);
-        for neighbor in neighbors {
-            rev.entry(neighbor.clone())
-                .or_insert_with(Vec::new)
-                .push(node.clone());
-        }
+// Count paths from `source` to `target` that pass every device of
+// `must_visit`, in any order, and none of `must_avoid`. Without cycles a path
+// can only meet its waypoints in topological order, so the count is the
+// product of the path counts between consecutive stops in that order, one DP
+// per stop.
+fn count_paths(
+    graph: &Graph,
+    source: &str,
+    target: &str,
+    must_visit: &[&str],
+    must_avoid: &[&str],
+    options: Options,
+) -> Result<u128, PathError> {
+    let (source, target) = (resolve(graph, &[source])?[0], resolve(graph, &[target])?[0]);
+    let avoid = resolve(graph, must_avoid)?;
+    let mut via = resolve(graph, must_visit)?;
+    via.sort_unstable();
+    via.dedup();
+    via.retain(|&v| v != source && v != target);
+    if [source, target]
+        .iter()
+        .chain(&via)
+        .any(|n| avoid.contains(n))
+    {
+        return Ok(0);
     }
 
-    rev
+    let graph = graph.without(&avoid);
+    let mut position = vec![None; graph.len()];
+    for (k, node) in topological_order(&graph, source)?.into_iter().enumerate() {
+        position[node as usize] = Some(k);
+    }
+    // A waypoint the source cannot reach is on no path
+    if via.iter().any(|&v| position[v as usize].is_none()) {
+        return Ok(0);
+    }
+    via.sort_unstable_by_key(|&v| position[v as usize]);
+
+    let stops: Vec<_> = std::iter::once(source)
+        .chain(via)
+        .chain(std::iter::once(target))
+        .collect();
+    let mut paths = options.reduce(1);
+    for leg in stops.windows(2) {
+        let from = count_paths_from(&graph, leg[0], options)?;
+        paths = options.mul(paths, from[leg[1] as usize])?;
+    }
+    Ok(paths)
 }
 
-// Count paths from all nodes to target using reverse graph
-fn count_paths_to(graph: &HashMap<String, Vec<String>>, target: &str) -> HashMap<String, usize> {
-    let rev = reverse_graph(graph);
-    count_paths_from(&rev, target)
+// `count_paths`, or the bounded simple path search if `options` ask for it
+fn query(
+    graph: &Graph,
+    source: &str,
+    target: &str,
+    must_visit: &[&str],
+    must_avoid: &[&str],
+    options: Options,
+) -> Result<u128, PathError> {
+    if !options.simple {
+        return count_paths(graph, source, target, must_visit, must_avoid, options);
+    }
+    let (source, target) = (resolve(graph, &[source])?[0], resolve(graph, &[target])?[0]);
+    let via = resolve(graph, must_visit)?;
+    let avoid = resolve(graph, must_avoid)?;
+    let count = count_simple_paths(graph, source, target, &via, &avoid, SIMPLE_PATH_STEPS)?;
+    Ok(options.reduce(count as u128))
 }
 
-fn solve_part1<R: BufRead>(reader: R) -> usize {
-    let graph = parse_input(reader);
-    let mut visited = HashSet::new();
-    count_paths(&graph, "you", "out", &mut visited)
+fn solve_part1<R: BufRead>(reader: R, options: Options) -> Result<u128, PathError> {
+    query(&parse_input(reader), "you", "out", &[], &[], options)
 }
 
-fn solve_part2<R: BufRead>(reader: R) -> usize {
-    let graph = parse_input(reader);
+fn solve_part2<R: BufRead>(reader: R, options: Options) -> Result<u128, PathError> {
+    query(
+        &parse_input(reader),
+        "svr",
+        "out",
+        &["fft", "dac"],
+        &[],
+        options,
+    )
+}
 
-    // Helper to count paths visiting nodes A then B
-    let count_via = |a: &str, b: &str| {
-        let from_start = count_paths_from(&graph, "svr");
-        let from_a = count_paths_from(&graph, a);
-        let to_end = count_paths_to(&graph, "out");
+fn main() {
+    let args = Args::from_env();
+
+    if args.is_command("generate") {
+        let mut rng = Rng::new(args.number("--seed").expect("Missing --seed"));
+        let nodes = args.number::<usize>("--nodes").unwrap_or(600);
+        let degree = args.number::<usize>("--degree").unwrap_or(3);
+        let reach = args.number::<usize>("--reach").unwrap_or(25);
+        print!("{}", generate::dag(&mut rng, nodes, degree, reach));
+        return;
+    }
 
-        from_start.get(a).unwrap_or(&0) * from_a.get(b).unwrap_or(&0) * to_end.get(b).unwrap_or(&0)
+    let options = Options {
+        simple: args.has("--simple-paths"),
+        modulus: args
+            .number("--modulo")
+            .inspect(|&m| assert!(m > 0, "Invalid --modulo")),
     };
 
-    count_via("fft", "dac") + count_via("dac", "fft")
-}
+    let report = |label: &str, result: Result<u128, PathError>| match result {
+        Ok(count) => println!("{label}: {count}"),
+        Err(e) => {
+            eprintln!("{label}: {e}");
+            if let PathError::Cycle(_) = e {
+                eprintln!("Use --simple-paths to count paths that repeat no device");
+            }
+            std::process::exit(1);
+        }
+    };
 
-fn read_input() -> BufReader<File> {
-    BufReader::new(File::open("../inputs/day11.txt").expect("Failed to open input file"))
-}
+    // An explicit query instead of the two puzzle parts
+    if let Some(source) = args.value("--from") {
+        let graph = parse_input(read_input(11));
+        let target = args.value("--to").unwrap_or("out");
+        let (via, avoid) = (args.list("--via"), args.list("--avoid"));
+        report(
+            "Paths",
+            query(&graph, source, target, &via, &avoid, options),
+        );
+        return;
+    }
 
-fn main() {
-    println!("Part 1: {}", solve_part1(read_input()));
-    println!("Part 2: {}", solve_part2(read_input()));
+    report("Part 1", solve_part1(read_input(11), options));
+    report("Part 2", solve_part2(read_input(11), options));
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc::diff::{self, Part};
     use std::io::Cursor;
 
+    const EXACT: Options = Options {
+        simple: false,
+        modulus: None,
+    };
+    const SIMPLE: Options = Options {
+        simple: true,
+        modulus: None,
+    };
+
+    const EXAMPLE_PART2: &str = "svr: aaa bbb
+aaa: fft
+fft: ccc
+bbb: tty
+tty: ccc
+ccc: ddd eee
+ddd: hub
+hub: fff
+eee: dac
+dac: fff
+fff: ggg hhh
+ggg: out
+hhh: out";
+
     #[test]
     fn test_example_part1() {
         let input = "aaa: you hhh
@@ -165,31 +367,173 @@ fff: out
 ggg: out
 hhh: ccc fff iii
 iii: out";
-        let reader = Cursor::new(input);
-        assert_eq!(solve_part1(reader), 5);
+        assert_eq!(solve_part1(Cursor::new(input), EXACT), Ok(5));
+        assert_eq!(solve_part1(Cursor::new(input), SIMPLE), Ok(5));
     }
 
     #[test]
     fn test_example_part2() {
-        let input = "svr: aaa bbb
-aaa: fft
-fft: ccc
-bbb: tty
-tty: ccc
-ccc: ddd eee
-ddd: hub
-hub: fff
-eee: dac
-dac: fff
-fff: ggg hhh
-ggg: out
-hhh: out";
-        let reader = Cursor::new(input);
-        assert_eq!(solve_part2(reader), 2);
+        let input = EXAMPLE_PART2;
+        assert_eq!(solve_part2(Cursor::new(input), EXACT), Ok(2));
+        assert_eq!(solve_part2(Cursor::new(input), SIMPLE), Ok(2));
+
+        // CRLF line endings would otherwise end up in the device names
+        let crlf = input.replace('\n', "\r\n");
+        assert_eq!(solve_part2(Cursor::new(&crlf), EXACT), Ok(2));
+    }
+
+    #[test]
+    fn test_waypoints() {
+        let graph = parse_input(Cursor::new(EXAMPLE_PART2));
+        let both = |via: &[&str], avoid: &[&str]| {
+            let dp = count_paths(&graph, "svr", "out", via, avoid, EXACT);
+            assert_eq!(dp, query(&graph, "svr", "out", via, avoid, SIMPLE));
+            dp.unwrap()
+        };
+
+        assert_eq!(both(&[], &[]), 8);
+        assert_eq!(both(&["dac", "fft"], &[]), 2);
+        assert_eq!(both(&["fft", "dac", "fft", "out"], &[]), 2);
+        assert_eq!(both(&["fft", "dac", "hub"], &[]), 0);
+        assert_eq!(both(&["ccc"], &["aaa"]), 4);
+        assert_eq!(both(&["hhh"], &["dac"]), 2);
+        assert_eq!(both(&["fft"], &["fft"]), 0);
+        assert_eq!(count_paths(&graph, "ccc", "hhh", &[], &[], EXACT), Ok(2));
+        assert_eq!(both(&["aaa", "bbb"], &[]), 0);
+        assert_eq!(
+            count_paths(&graph, "svr", "out", &["xyz"], &[], EXACT),
+            Err(PathError::UnknownDevice("xyz".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_many_waypoints() {
+        // Every node of a long chain as a waypoint, given back to front
+        let mut graph = Graph::new();
+        for k in 0..30 {
+            graph.add_edge(&format!("n{k}"), &format!("n{}", k + 1));
+            graph.add_edge(&format!("n{k}"), &format!("x{k}"));
+            graph.add_edge(&format!("x{k}"), &format!("n{}", k + 1));
+        }
+        let via: Vec<_> = (1..30).rev().map(|k| format!("n{k}")).collect();
+        let via: Vec<_> = via.iter().map(String::as_str).collect();
+        assert_eq!(
+            count_paths(&graph, "n0", "n30", &via, &[], EXACT),
+            Ok(1 << 30)
+        );
+        assert_eq!(
+            count_paths(&graph, "n0", "n30", &via, &["x7"], EXACT),
+            Ok(1 << 29)
+        );
+        assert_eq!(count_paths(&graph, "n5", "n30", &via, &[], EXACT), Ok(0));
+    }
+
+    #[test]
+    fn test_overflow() {
+        // A chain of diamonds doubles the number of paths at every link
+        let chain = |links: usize| {
+            let mut graph = Graph::new();
+            for k in 0..links {
+                let (from, to) = (format!("n{k}"), format!("n{}", k + 1));
+                for side in ["l", "r"] {
+                    graph.add_edge(&from, &format!("{side}{k}"));
+                    graph.add_edge(&format!("{side}{k}"), &to);
+                }
+            }
+            graph
+        };
+        let modulo = Options {
+            simple: false,
+            modulus: Some(1_000_000_007),
+        };
+
+        let graph = chain(127);
+        assert_eq!(query(&graph, "n0", "n127", &[], &[], EXACT), Ok(1 << 127));
+        assert_eq!(query(&graph, "n0", "n127", &[], &[], modulo), Ok(639816142));
+
+        let graph = chain(130);
+        assert_eq!(
+            query(&graph, "n0", "n130", &[], &[], EXACT),
+            Err(PathError::Overflow)
+        );
+        assert_eq!(
+            query(&graph, "n0", "n130", &["n65"], &[], EXACT),
+            Err(PathError::Overflow)
+        );
+        assert_eq!(query(&graph, "n0", "n130", &[], &[], modulo), Ok(118529101));
+        assert_eq!(
+            query(&graph, "n0", "n130", &["n65"], &[], modulo),
+            Ok(118529101)
+        );
+    }
+
+    #[test]
+    fn test_cycle() {
+        // ccc and eee feed each other, so there are infinitely many paths
+        let input = "you: bbb ccc
+bbb: ccc
+ccc: eee out
+eee: ccc";
+        let error = solve_part1(Cursor::new(input), EXACT).unwrap_err();
+        assert_eq!(
+            error,
+            PathError::Cycle(vec!["ccc".to_string(), "eee".to_string()])
+        );
+        assert_eq!(
+            error.to_string(),
+            "device graph has a cycle: ccc -> eee -> ccc"
+        );
+        assert_eq!(solve_part1(Cursor::new(input), SIMPLE), Ok(2));
+
+        let graph = parse_input(Cursor::new(input));
+        let (you, out) = (graph.id("you").unwrap(), graph.id("out").unwrap());
+        assert_eq!(count_simple_paths(&graph, you, out, &[], &[], 8), Ok(2));
+        assert_eq!(
+            count_simple_paths(&graph, you, out, &[], &[], 7),
+            Err(PathError::TooManySteps(7))
+        );
+    }
+
+    #[test]
+    fn test_long_chain() {
+        // Deeper than a recursive search could go on the default stack
+        let mut graph = Graph::new();
+        let length = 300_000;
+        for k in 0..length {
+            graph.add_edge(&format!("n{k}"), &format!("n{}", k + 1));
+        }
+        let (first, last) = (graph.id("n0").unwrap(), graph.id("n300000").unwrap());
+        let via = [graph.id("n150000").unwrap()];
+        assert_eq!(
+            count_simple_paths(&graph, first, last, &via, &[], length + 1),
+            Ok(1)
+        );
+        assert_eq!(
+            count_simple_paths(&graph, first, last, &[], &[], length),
+            Err(PathError::TooManySteps(length))
+        );
+    }
+
+    #[test]
+    fn test_differential() {
+        let parts = [
+            Part::new(
+                "day11 part 1",
+                |input: &str| solve_part1(Cursor::new(input), SIMPLE),
+                |input: &str| solve_part1(Cursor::new(input), EXACT),
+            ),
+            Part::new(
+                "day11 part 2",
+                |input: &str| solve_part2(Cursor::new(input), SIMPLE),
+                |input: &str| solve_part2(Cursor::new(input), EXACT),
+            ),
+        ];
+        let inputs = (0..20).map(|seed| generate::dag(&mut Rng::new(seed), 40, 3, 6));
+        diff::check(&parts, inputs).unwrap_or_else(|m| panic!("{m}"));
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(solve_part2(read_input()), 473930047491888);
+        assert_eq!(solve_part2(read_input(11), EXACT), Ok(473930047491888));
     }
 }
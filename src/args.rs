@@ -0,0 +1,84 @@
+use std::str::FromStr;
+
+// Command line of a day: an optional subcommand such as `generate`, then
+// `--flag value` pairs and bare `--switch`es in any order. Bad values are
+// mistakes at the command line, so they panic with the flag in the message.
+pub struct Args {
+    args: Vec<String>,
+}
+
+impl Args {
+    pub fn new<I: IntoIterator<Item = S>, S: Into<String>>(args: I) -> Self {
+        Self {
+            args: args.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    // The arguments the program was started with
+    pub fn from_env() -> Self {
+        Self::new(std::env::args().skip(1))
+    }
+
+    pub fn is_command(&self, name: &str) -> bool {
+        self.args.first().is_some_and(|a| a == name)
+    }
+
+    pub fn has(&self, switch: &str) -> bool {
+        self.args.iter().any(|a| a == switch)
+    }
+
+    // The argument after `flag`, if `flag` is given
+    pub fn value(&self, flag: &str) -> Option<&str> {
+        let pos = self.args.iter().position(|a| a == flag)?;
+        let value = self
+            .args
+            .get(pos + 1)
+            .unwrap_or_else(|| panic!("Missing value for {flag}"));
+        Some(value)
+    }
+
+    pub fn number<T: FromStr>(&self, flag: &str) -> Option<T> {
+        self.value(flag).map(|v| {
+            v.parse()
+                .unwrap_or_else(|_| panic!("Invalid number for {flag}: {v:?}"))
+        })
+    }
+
+    // The comma-separated items after `flag`, none if it is not given
+    pub fn list(&self, flag: &str) -> Vec<&str> {
+        self.value(flag)
+            .map_or(Vec::new(), |v| v.split(',').collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flags() {
+        let args = Args::new(["generate", "--seed", "7", "--svg", "--via", "fft,dac"]);
+        assert!(args.is_command("generate"));
+        assert!(!args.is_command("--seed"));
+        assert!(args.has("--svg"));
+        assert!(!args.has("--top"));
+        assert_eq!(args.value("--via"), Some("fft,dac"));
+        assert_eq!(args.number::<u64>("--seed"), Some(7));
+        assert_eq!(args.number::<usize>("--count"), None);
+        assert_eq!(args.list("--via"), ["fft", "dac"]);
+        assert!(args.list("--avoid").is_empty());
+        assert!(!Args::new(Vec::<String>::new()).is_command("generate"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid number for --seed: \"x\"")]
+    fn test_invalid_number() {
+        Args::new(["--seed", "x"]).number::<u64>("--seed");
+    }
+
+    #[test]
+    #[should_panic(expected = "Missing value for --seed")]
+    fn test_missing_value() {
+        Args::new(["generate", "--seed"]).value("--seed");
+    }
+}
@@ -0,0 +1,224 @@
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+
+// What a solver made of an input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Answer(String),
+    Panic(String),
+}
+
+impl Outcome {
+    // Run `solve`, turning a panic into an outcome of its own
+    fn of(solve: &dyn Fn(&str) -> String, input: &str) -> Self {
+        match panic::catch_unwind(AssertUnwindSafe(|| solve(input))) {
+            Ok(answer) => Outcome::Answer(answer),
+            Err(payload) => Outcome::Panic(
+                payload
+                    .downcast_ref::<&str>()
+                    .map(|s| s.to_string())
+                    .or_else(|| payload.downcast_ref::<String>().cloned())
+                    .unwrap_or_default(),
+            ),
+        }
+    }
+
+    // Solvers agree when they give the same answer or both reject the input
+    fn agrees_with(&self, other: &Outcome) -> bool {
+        match (self, other) {
+            (Outcome::Answer(a), Outcome::Answer(b)) => a == b,
+            (Outcome::Panic(_), Outcome::Panic(_)) => true,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Outcome::Answer(answer) => write!(f, "{answer}"),
+            Outcome::Panic(message) => write!(f, "panicked: {message}"),
+        }
+    }
+}
+
+type Solver<'a> = Box<dyn Fn(&str) -> String + 'a>;
+
+// One part of a day, solved both by a slow reference that is easy to trust
+// and by the optimized solver it checks
+pub struct Part<'a> {
+    name: &'a str,
+    reference: Solver<'a>,
+    optimized: Solver<'a>,
+    // Inputs outside this are skipped, for parsers that assume more than
+    // they check
+    valid: Box<dyn Fn(&str) -> bool + 'a>,
+}
+
+impl<'a> Part<'a> {
+    pub fn new<T, R, O>(name: &'a str, reference: R, optimized: O) -> Self
+    where
+        T: fmt::Debug,
+        R: Fn(&str) -> T + 'a,
+        O: Fn(&str) -> T + 'a,
+    {
+        Self {
+            name,
+            reference: Box::new(move |input| format!("{:?}", reference(input))),
+            optimized: Box::new(move |input| format!("{:?}", optimized(input))),
+            valid: Box::new(|_| true),
+        }
+    }
+
+    pub fn valid_when(mut self, valid: impl Fn(&str) -> bool + 'a) -> Self {
+        self.valid = Box::new(valid);
+        self
+    }
+
+    fn disagreement(&self, input: &str) -> Option<(Outcome, Outcome)> {
+        if !(self.valid)(input) {
+            return None;
+        }
+        let reference = Outcome::of(&self.reference, input);
+        let optimized = Outcome::of(&self.optimized, input);
+        (!reference.agrees_with(&optimized)).then_some((reference, optimized))
+    }
+
+    // Compare the solvers on `input`, and shrink it if they disagree
+    pub fn check(&self, input: &str) -> Result<(), Mismatch> {
+        if self.disagreement(input).is_none() {
+            return Ok(());
+        }
+        let input = shrink(input, |candidate| self.disagreement(candidate).is_some());
+        let (reference, optimized) = self.disagreement(&input).unwrap();
+        Err(Mismatch {
+            part: self.name.to_string(),
+            input,
+            reference,
+            optimized,
+        })
+    }
+}
+
+// A smallest known input on which the solvers of a part disagree
+#[derive(Debug)]
+pub struct Mismatch {
+    pub part: String,
+    pub input: String,
+    pub reference: Outcome,
+    pub optimized: Outcome,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}: reference and optimized solvers disagree", self.part)?;
+        writeln!(f, "  reference: {}", self.reference)?;
+        writeln!(f, "  optimized: {}", self.optimized)?;
+        writeln!(f, "on input:")?;
+        write!(f, "{}", self.input)
+    }
+}
+
+// Check every part on every input, stopping at the first disagreement
+pub fn check<I>(parts: &[Part], inputs: I) -> Result<(), Mismatch>
+where
+    I: IntoIterator<Item = String>,
+{
+    for input in inputs {
+        for part in parts {
+            part.check(&input)?;
+        }
+    }
+    Ok(())
+}
+
+// Delete lines from `input` while it still `fails`: first halves, then ever
+// smaller runs, down to single lines, until no single line can go. The result
+// keeps the line order and a trailing newline.
+pub fn shrink(input: &str, fails: impl Fn(&str) -> bool) -> String {
+    let join = |lines: &[&str]| lines.iter().map(|l| format!("{l}\n")).collect::<String>();
+    let mut lines: Vec<&str> = input.lines().collect();
+    let mut run = lines.len().div_ceil(2).max(1);
+
+    loop {
+        let mut removed = false;
+        let mut start = 0;
+        while start < lines.len() {
+            let end = (start + run).min(lines.len());
+            let candidate: Vec<&str> = [&lines[..start], &lines[end..]].concat();
+            if fails(&join(&candidate)) {
+                lines = candidate;
+                removed = true;
+            } else {
+                start = end;
+            }
+        }
+        if run == 1 && !removed {
+            break;
+        }
+        run = (run / 2).max(1);
+    }
+
+    join(&lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn total(input: &str) -> u64 {
+        input.lines().map(|l| l.parse::<u64>().unwrap()).sum()
+    }
+
+    #[test]
+    fn test_shrink() {
+        let fails =
+            |input: &str| input.lines().any(|l| l == "3") && input.lines().any(|l| l == "8");
+        let input: String = (0..20).map(|n| format!("{n}\n")).collect();
+        assert_eq!(shrink(&input, fails), "3\n8\n");
+        assert_eq!(shrink("only\n", |_| true), "");
+    }
+
+    #[test]
+    fn test_check() {
+        let inputs = || (1..30).map(|n| (0..n).map(|k| format!("{}\n", k * 7 % 11)).collect());
+
+        let exact = Part::new("sum", total, |input: &str| {
+            input
+                .lines()
+                .fold(0, |acc, l| acc + l.parse::<u64>().unwrap())
+        });
+        assert!(check(&[exact], inputs()).is_ok());
+
+        // Loses every 9 after the first line
+        let lossy = Part::new("sum", total, |input: &str| {
+            let skip = |(k, l): (usize, &str)| k > 0 && l == "9";
+            input
+                .lines()
+                .enumerate()
+                .filter(|&p| !skip(p))
+                .map(|(_, l)| l.parse::<u64>().unwrap())
+                .sum::<u64>()
+        });
+        let mismatch = check(&[lossy], inputs()).unwrap_err();
+        assert_eq!(mismatch.input.lines().count(), 2);
+        assert!(mismatch.input.ends_with("9\n"));
+        assert_eq!(
+            mismatch.reference,
+            Outcome::Answer(format!("{}", total(&mismatch.input)))
+        );
+
+        // Panics on an empty line, which is fine if such inputs are invalid
+        let lenient = |input: &str| -> u64 { input.lines().map(|l| l.parse().unwrap_or(0)).sum() };
+        let fragile = Part::new("sum", lenient, total);
+        let mismatch = fragile.check("1\n\n2\n").unwrap_err();
+        assert_eq!(mismatch.input, "\n");
+        assert!(matches!(mismatch.optimized, Outcome::Panic(_)));
+        assert!(
+            fragile
+                .valid_when(|input| input.lines().all(|l| !l.is_empty()))
+                .check("1\n\n2\n")
+                .is_ok()
+        );
+    }
+}
@@ -0,0 +1,363 @@
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+// Integer coordinate type. Products are taken after widening to i128, so
+// distances and cross products cannot overflow for any of these.
+pub trait Coord:
+    Copy + Ord + Default + fmt::Debug + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self>
+{
+    fn widen(self) -> i128;
+}
+
+macro_rules! coord {
+    ($($t:ty),*) => {
+        $(impl Coord for $t {
+            fn widen(self) -> i128 {
+                self as i128
+            }
+        })*
+    };
+}
+
+coord!(i32, i64, isize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Point2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Point3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T> Point2<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+}
+
+impl<T> Point3<T> {
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Self { x, y, z }
+    }
+}
+
+fn abs_diff<T: Coord>(a: T, b: T) -> u128 {
+    (a.widen() - b.widen()).unsigned_abs()
+}
+
+impl<T: Coord> Point2<T> {
+    pub fn manhattan(self, other: Self) -> u128 {
+        abs_diff(self.x, other.x) + abs_diff(self.y, other.y)
+    }
+
+    // Orders pairs exactly like the Euclidean distance, without rounding
+    pub fn distance_squared(self, other: Self) -> u128 {
+        abs_diff(self.x, other.x).pow(2) + abs_diff(self.y, other.y).pow(2)
+    }
+
+    pub fn distance(self, other: Self) -> f64 {
+        (self.distance_squared(other) as f64).sqrt()
+    }
+
+    // z component of the cross product of the two vectors
+    pub fn cross(self, other: Self) -> i128 {
+        self.x.widen() * other.y.widen() - self.y.widen() * other.x.widen()
+    }
+}
+
+impl<T: Coord> Point3<T> {
+    pub fn manhattan(self, other: Self) -> u128 {
+        abs_diff(self.x, other.x) + abs_diff(self.y, other.y) + abs_diff(self.z, other.z)
+    }
+
+    // Exact for coordinates below 2^62 in absolute value
+    pub fn distance_squared(self, other: Self) -> u128 {
+        abs_diff(self.x, other.x).pow(2)
+            + abs_diff(self.y, other.y).pow(2)
+            + abs_diff(self.z, other.z).pow(2)
+    }
+
+    pub fn distance(self, other: Self) -> f64 {
+        (self.distance_squared(other) as f64).sqrt()
+    }
+}
+
+macro_rules! ops {
+    ($point:ident { $($f:ident),* }) => {
+        impl<T: Add<Output = T>> Add for $point<T> {
+            type Output = Self;
+
+            fn add(self, other: Self) -> Self {
+                $point { $($f: self.$f + other.$f),* }
+            }
+        }
+
+        impl<T: Sub<Output = T>> Sub for $point<T> {
+            type Output = Self;
+
+            fn sub(self, other: Self) -> Self {
+                $point { $($f: self.$f - other.$f),* }
+            }
+        }
+
+        impl<T: Neg<Output = T>> Neg for $point<T> {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                $point { $($f: -self.$f),* }
+            }
+        }
+
+        // Scaling by a scalar
+        impl<T: Mul<Output = T> + Copy> Mul<T> for $point<T> {
+            type Output = Self;
+
+            fn mul(self, k: T) -> Self {
+                $point { $($f: self.$f * k),* }
+            }
+        }
+
+        impl<T: Add<Output = T> + Copy> AddAssign for $point<T> {
+            fn add_assign(&mut self, other: Self) {
+                *self = *self + other;
+            }
+        }
+
+        impl<T: Sub<Output = T> + Copy> SubAssign for $point<T> {
+            fn sub_assign(&mut self, other: Self) {
+                *self = *self - other;
+            }
+        }
+    };
+}
+
+ops!(Point2 { x, y });
+ops!(Point3 { x, y, z });
+
+// Turn taken going from `a` to `b` to `c`, with y pointing up
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    Clockwise,
+    CounterClockwise,
+    Collinear,
+}
+
+pub fn orientation<T: Coord>(a: Point2<T>, b: Point2<T>, c: Point2<T>) -> Orientation {
+    let (ax, ay) = (a.x.widen(), a.y.widen());
+    let turn = (b.x.widen() - ax) * (c.y.widen() - ay) - (b.y.widen() - ay) * (c.x.widen() - ax);
+    match turn.signum() {
+        1 => Orientation::CounterClockwise,
+        -1 => Orientation::Clockwise,
+        _ => Orientation::Collinear,
+    }
+}
+
+// Axis-aligned rectangle including its boundary, `min` <= `max` on both axes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rect<T> {
+    pub min: Point2<T>,
+    pub max: Point2<T>,
+}
+
+impl<T: Coord> Rect<T> {
+    // The rectangle with `a` and `b` at opposite corners
+    pub fn from_corners(a: Point2<T>, b: Point2<T>) -> Self {
+        Self {
+            min: Point2::new(a.x.min(b.x), a.y.min(b.y)),
+            max: Point2::new(a.x.max(b.x), a.y.max(b.y)),
+        }
+    }
+
+    pub fn width(&self) -> u128 {
+        abs_diff(self.max.x, self.min.x)
+    }
+
+    pub fn height(&self) -> u128 {
+        abs_diff(self.max.y, self.min.y)
+    }
+
+    pub fn area(&self) -> u128 {
+        self.width() * self.height()
+    }
+
+    // Number of integer points inside or on the rectangle, i.e. its area
+    // counted in unit tiles centred on the coordinates
+    pub fn lattice_points(&self) -> u128 {
+        (self.width() + 1) * (self.height() + 1)
+    }
+
+    pub fn contains(&self, p: Point2<T>) -> bool {
+        (self.min.x..=self.max.x).contains(&p.x) && (self.min.y..=self.max.y).contains(&p.y)
+    }
+
+    // Whether the two share any point, boundaries included
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.min.x <= other.max.x
+            && other.min.x <= self.max.x
+            && self.min.y <= other.max.y
+            && other.min.y <= self.max.y
+    }
+}
+
+// Axis-aligned box including its boundary, `min` <= `max` on all axes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Box3<T> {
+    pub min: Point3<T>,
+    pub max: Point3<T>,
+}
+
+impl<T: Coord> Box3<T> {
+    pub fn from_corners(a: Point3<T>, b: Point3<T>) -> Self {
+        Self {
+            min: Point3::new(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z)),
+            max: Point3::new(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z)),
+        }
+    }
+
+    pub fn volume(&self) -> u128 {
+        abs_diff(self.max.x, self.min.x)
+            * abs_diff(self.max.y, self.min.y)
+            * abs_diff(self.max.z, self.min.z)
+    }
+
+    pub fn contains(&self, p: Point3<T>) -> bool {
+        (self.min.x..=self.max.x).contains(&p.x)
+            && (self.min.y..=self.max.y).contains(&p.y)
+            && (self.min.z..=self.max.z).contains(&p.z)
+    }
+
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.min.x <= other.max.x
+            && other.min.x <= self.max.x
+            && self.min.y <= other.max.y
+            && other.min.y <= self.max.y
+            && self.min.z <= other.max.z
+            && other.min.z <= self.max.z
+    }
+}
+
+// Closed line segment between two points
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Segment<T> {
+    pub a: Point2<T>,
+    pub b: Point2<T>,
+}
+
+impl<T: Coord> Segment<T> {
+    pub fn new(a: Point2<T>, b: Point2<T>) -> Self {
+        Self { a, b }
+    }
+
+    pub fn is_axis_aligned(&self) -> bool {
+        self.a.x == self.b.x || self.a.y == self.b.y
+    }
+
+    pub fn bounds(&self) -> Rect<T> {
+        Rect::from_corners(self.a, self.b)
+    }
+
+    pub fn contains(&self, p: Point2<T>) -> bool {
+        orientation(self.a, self.b, p) == Orientation::Collinear && self.bounds().contains(p)
+    }
+
+    // Whether the two segments share any point, endpoints included
+    pub fn intersects(&self, other: &Self) -> bool {
+        let (o1, o2) = (
+            orientation(self.a, self.b, other.a),
+            orientation(self.a, self.b, other.b),
+        );
+        let (o3, o4) = (
+            orientation(other.a, other.b, self.a),
+            orientation(other.a, other.b, self.b),
+        );
+
+        (o1 != o2 && o3 != o4 && !has_collinear([o1, o2, o3, o4]))
+            || self.contains(other.a)
+            || self.contains(other.b)
+            || other.contains(self.a)
+            || other.contains(self.b)
+    }
+}
+
+fn has_collinear(orientations: [Orientation; 4]) -> bool {
+    orientations.contains(&Orientation::Collinear)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_points() {
+        let (a, b) = (Point2::new(1, 2), Point2::new(4, -2));
+        assert_eq!(a + b, Point2::new(5, 0));
+        assert_eq!(a - b, Point2::new(-3, 4));
+        assert_eq!(-a * 2, Point2::new(-2, -4));
+        assert_eq!(a.manhattan(b), 7);
+        assert_eq!(a.distance_squared(b), 25);
+        assert_eq!(a.distance(b), 5.0);
+
+        let (p, q) = (Point3::new(0i64, 0, 0), Point3::new(2, -3, 6));
+        assert_eq!(p.distance_squared(q), 49);
+        assert_eq!(p.manhattan(q), 11);
+
+        // Differences this large overflow an i64 before widening
+        let far = Point3::new(i64::MIN, 0, 0);
+        let near = Point3::new(i64::MAX, 0, 0);
+        assert_eq!(far.distance_squared(near), (u64::MAX as u128).pow(2));
+    }
+
+    #[test]
+    fn test_orientation() {
+        let (a, b) = (Point2::new(0, 0), Point2::new(4, 0));
+        assert_eq!(
+            orientation(a, b, Point2::new(2, 1)),
+            Orientation::CounterClockwise
+        );
+        assert_eq!(
+            orientation(a, b, Point2::new(2, -1)),
+            Orientation::Clockwise
+        );
+        assert_eq!(orientation(a, b, Point2::new(9, 0)), Orientation::Collinear);
+    }
+
+    #[test]
+    fn test_rect() {
+        let r = Rect::from_corners(Point2::new(9, 5), Point2::new(2, 3));
+        assert_eq!((r.min, r.max), (Point2::new(2, 3), Point2::new(9, 5)));
+        assert_eq!((r.area(), r.lattice_points()), (14, 24));
+        assert!(r.contains(Point2::new(2, 4)));
+        assert!(!r.contains(Point2::new(1, 4)));
+        assert!(r.intersects(&Rect::from_corners(Point2::new(9, 5), Point2::new(12, 12))));
+        assert!(!r.intersects(&Rect::from_corners(Point2::new(10, 5), Point2::new(12, 12))));
+
+        let b = Box3::from_corners(Point3::new(0, 0, 0), Point3::new(2, 3, 4));
+        assert_eq!(b.volume(), 24);
+        assert!(b.contains(Point3::new(2, 3, 4)));
+        assert!(!b.intersects(&Box3::from_corners(
+            Point3::new(0, 0, 5),
+            Point3::new(1, 1, 6)
+        )));
+    }
+
+    #[test]
+    fn test_segments() {
+        let s = |a: (i64, i64), b: (i64, i64)| {
+            Segment::new(Point2::new(a.0, a.1), Point2::new(b.0, b.1))
+        };
+        assert!(s((0, 0), (4, 4)).intersects(&s((0, 4), (4, 0))));
+        assert!(!s((0, 0), (4, 4)).intersects(&s((1, 0), (5, 4))));
+        // Touching at an endpoint, and overlapping along a line
+        assert!(s((0, 0), (4, 0)).intersects(&s((4, 0), (4, 3))));
+        assert!(s((0, 0), (4, 0)).intersects(&s((2, 0), (6, 0))));
+        assert!(!s((0, 0), (4, 0)).intersects(&s((5, 0), (6, 0))));
+        assert!(s((0, 0), (4, 0)).contains(Point2::new(3, 0)));
+        assert!(s((0, 0), (0, 4)).is_axis_aligned());
+        assert!(!s((0, 0), (4, 4)).is_axis_aligned());
+    }
+}
@@ -0,0 +1,236 @@
+use std::collections::HashMap;
+
+// Dense id of an interned node name
+pub type NodeId = u32;
+
+// Directed graph over string-named nodes. Names are interned once to dense
+// ids, so traversals work on plain vectors indexed by id.
+#[derive(Clone, Default)]
+pub struct Graph {
+    names: Vec<String>,
+    ids: HashMap<String, NodeId>,
+    edges: Vec<Vec<NodeId>>,
+}
+
+// Nodes of a directed cycle, each with an edge to the next and the last
+// back to the first
+#[derive(Debug, PartialEq, Eq)]
+pub struct Cycle(pub Vec<NodeId>);
+
+impl Graph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    // Id of `name`, adding it as a node without edges if it is new
+    pub fn intern(&mut self, name: &str) -> NodeId {
+        if let Some(&id) = self.ids.get(name) {
+            return id;
+        }
+        let id = NodeId::try_from(self.names.len()).expect("Too many nodes");
+        self.names.push(name.to_string());
+        self.ids.insert(name.to_string(), id);
+        self.edges.push(Vec::new());
+        id
+    }
+
+    pub fn id(&self, name: &str) -> Option<NodeId> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, id: NodeId) -> &str {
+        &self.names[id as usize]
+    }
+
+    pub fn add_edge(&mut self, from: &str, to: &str) {
+        let (from, to) = (self.intern(from), self.intern(to));
+        self.edges[from as usize].push(to);
+    }
+
+    pub fn neighbours(&self, id: NodeId) -> &[NodeId] {
+        &self.edges[id as usize]
+    }
+
+    // Same nodes with the same ids, every edge flipped
+    pub fn reverse(&self) -> Self {
+        let mut edges = vec![Vec::new(); self.len()];
+        for (from, neighbours) in self.edges.iter().enumerate() {
+            for &to in neighbours {
+                edges[to as usize].push(from as NodeId);
+            }
+        }
+        Self {
+            names: self.names.clone(),
+            ids: self.ids.clone(),
+            edges,
+        }
+    }
+
+    // Same nodes with the same ids, minus every edge into or out of `removed`
+    pub fn without(&self, removed: &[NodeId]) -> Self {
+        let mut gone = vec![false; self.len()];
+        for &node in removed {
+            gone[node as usize] = true;
+        }
+        let edges = self
+            .edges
+            .iter()
+            .enumerate()
+            .map(|(from, neighbours)| {
+                if gone[from] {
+                    return Vec::new();
+                }
+                neighbours
+                    .iter()
+                    .copied()
+                    .filter(|&to| !gone[to as usize])
+                    .collect()
+            })
+            .collect();
+        Self {
+            names: self.names.clone(),
+            ids: self.ids.clone(),
+            edges,
+        }
+    }
+
+    // Nodes reachable from `source`, each before all of its successors, or
+    // the first cycle met on the way, in edge order starting anywhere on it.
+    // Iterative, so deep graphs cannot overflow the stack.
+    pub fn topological_order(&self, source: NodeId) -> Result<Vec<NodeId>, Cycle> {
+        let mut visited = vec![false; self.len()];
+        let mut on_stack = vec![false; self.len()];
+        let mut order = Vec::new();
+        let mut stack = vec![(source, 0)];
+        visited[source as usize] = true;
+        on_stack[source as usize] = true;
+
+        while let Some((node, next)) = stack.last_mut() {
+            let node = *node;
+            match self.edges[node as usize].get(*next) {
+                Some(&child) => {
+                    *next += 1;
+                    if on_stack[child as usize] {
+                        let start = stack.iter().position(|&(n, _)| n == child).unwrap();
+                        return Err(Cycle(stack[start..].iter().map(|&(n, _)| n).collect()));
+                    }
+                    if !visited[child as usize] {
+                        visited[child as usize] = true;
+                        on_stack[child as usize] = true;
+                        stack.push((child, 0));
+                    }
+                }
+                None => {
+                    on_stack[node as usize] = false;
+                    order.push(node);
+                    stack.pop();
+                }
+            }
+        }
+
+        order.reverse();
+        Ok(order)
+    }
+
+    // Which nodes can be reached from `source`, indexed by id
+    pub fn reachable(&self, source: NodeId) -> Vec<bool> {
+        let mut seen = vec![false; self.len()];
+        let mut stack = vec![source];
+        seen[source as usize] = true;
+        while let Some(node) = stack.pop() {
+            for &next in self.neighbours(node) {
+                if !seen[next as usize] {
+                    seen[next as usize] = true;
+                    stack.push(next);
+                }
+            }
+        }
+        seen
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diamond() -> Graph {
+        let mut graph = Graph::new();
+        graph.add_edge("a", "b");
+        graph.add_edge("a", "c");
+        graph.add_edge("b", "d");
+        graph.add_edge("c", "d");
+        graph.intern("e");
+        graph
+    }
+
+    #[test]
+    fn test_interning() {
+        let mut graph = diamond();
+        assert_eq!(graph.len(), 5);
+        assert_eq!(graph.id("c"), Some(2));
+        assert_eq!(graph.id("z"), None);
+        assert_eq!(graph.intern("d"), 3);
+        assert_eq!(graph.name(4), "e");
+        assert_eq!(graph.neighbours(0), &[1, 2]);
+
+        let rev = graph.reverse();
+        assert_eq!(rev.id("c"), Some(2));
+        assert_eq!(rev.neighbours(3), &[1, 2]);
+        assert!(rev.neighbours(0).is_empty());
+
+        let cut = graph.without(&[1]);
+        assert_eq!(cut.len(), 5);
+        assert_eq!(cut.neighbours(0), &[2]);
+        assert!(cut.neighbours(1).is_empty());
+    }
+
+    #[test]
+    fn test_order_and_reachability() {
+        let graph = diamond();
+        let order = graph.topological_order(0).unwrap();
+        assert_eq!(order.len(), 4);
+        let position = |id| order.iter().position(|&n| n == id).unwrap();
+        assert_eq!(position(0), 0);
+        assert_eq!(position(3), 3);
+
+        assert_eq!(graph.reachable(1), vec![false, true, false, true, false]);
+        assert_eq!(
+            graph.reverse().reachable(3),
+            vec![true, true, true, true, false]
+        );
+    }
+
+    #[test]
+    fn test_cycle() {
+        let mut graph = diamond();
+        graph.add_edge("d", "e");
+        graph.add_edge("e", "c");
+        assert_eq!(graph.topological_order(0), Err(Cycle(vec![3, 4, 2])));
+        assert_eq!(graph.topological_order(2), Err(Cycle(vec![2, 3, 4])));
+
+        // A loop the source cannot reach is not in the way
+        let mut graph = diamond();
+        graph.add_edge("e", "e");
+        assert!(graph.topological_order(0).is_ok());
+        assert_eq!(graph.topological_order(4), Err(Cycle(vec![4])));
+    }
+
+    #[test]
+    fn test_long_path() {
+        let mut graph = Graph::new();
+        for i in 1..200_000 {
+            graph.add_edge(&(i - 1).to_string(), &i.to_string());
+        }
+        let order = graph.topological_order(0).unwrap();
+        assert_eq!(order.len(), 200_000);
+        assert_eq!(graph.name(order[199_999]), "199999");
+    }
+}
@@ -0,0 +1,306 @@
+use crate::geometry::Point2;
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+// Cell position, x to the right and y downwards. Signed so that stepping off
+// the grid yields a position `Grid::get` rejects rather than an underflow.
+pub type Point = Point2<isize>;
+
+impl Point {
+    pub const UP: Point = Point::new(0, -1);
+    pub const DOWN: Point = Point::new(0, 1);
+    pub const LEFT: Point = Point::new(-1, 0);
+    pub const RIGHT: Point = Point::new(1, 0);
+
+    pub const ORTHOGONAL: [Point; 4] = [Point::UP, Point::RIGHT, Point::DOWN, Point::LEFT];
+    pub const ADJACENT: [Point; 8] = [
+        Point::new(-1, -1),
+        Point::UP,
+        Point::new(1, -1),
+        Point::RIGHT,
+        Point::new(1, 1),
+        Point::DOWN,
+        Point::new(-1, 1),
+        Point::LEFT,
+    ];
+}
+
+// A line of the text whose width differs from the first line's
+#[derive(Debug, PartialEq, Eq)]
+pub struct RaggedLine {
+    pub line: usize,
+    pub width: usize,
+    pub expected: usize,
+}
+
+impl fmt::Display for RaggedLine {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {} is {} cells wide, expected {}",
+            self.line, self.width, self.expected
+        )
+    }
+}
+
+// Rectangular grid stored as one row-major vector
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    // Grid from its rows, which must all be `width` cells long
+    pub fn from_rows(width: usize, rows: impl IntoIterator<Item = Vec<T>>) -> Self {
+        let mut cells = Vec::new();
+        let mut height = 0;
+        for row in rows {
+            assert_eq!(row.len(), width, "Row {height} has the wrong width");
+            cells.extend(row);
+            height += 1;
+        }
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        (0..self.width as isize).contains(&p.x) && (0..self.height as isize).contains(&p.y)
+    }
+
+    fn offset(&self, p: Point) -> Option<usize> {
+        self.contains(p)
+            .then(|| p.y as usize * self.width + p.x as usize)
+    }
+
+    pub fn get(&self, p: Point) -> Option<&T> {
+        self.offset(p).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, p: Point) -> Option<&mut T> {
+        self.offset(p).map(|i| &mut self.cells[i])
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "Column {x} is outside the grid");
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // chunks() rejects a zero width, which only an empty grid can have
+        self.cells.chunks(self.width.max(1))
+    }
+
+    // Every position, row by row. Borrows nothing, so the grid can be
+    // modified while walking it.
+    pub fn points(&self) -> impl Iterator<Item = Point> + use<T> {
+        let (width, height) = (self.width as isize, self.height as isize);
+        (0..height).flat_map(move |y| (0..width).map(move |x| Point::new(x, y)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(&self.cells)
+    }
+
+    // Neighbours of `p` among the given offsets that lie on the grid
+    fn neighbours<'a>(
+        &'a self,
+        p: Point,
+        offsets: &'static [Point],
+    ) -> impl Iterator<Item = Point> + 'a {
+        offsets
+            .iter()
+            .map(move |&d| p + d)
+            .filter(|&q| self.contains(q))
+    }
+
+    pub fn neighbours4(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        self.neighbours(p, &Point::ORTHOGONAL)
+    }
+
+    pub fn neighbours8(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        self.neighbours(p, &Point::ADJACENT)
+    }
+
+    // First position holding `value`, row by row
+    pub fn find(&self, value: &T) -> Option<Point>
+    where
+        T: PartialEq,
+    {
+        self.iter().find(|(_, v)| *v == value).map(|(p, _)| p)
+    }
+
+    pub fn map<U>(&self, mut f: impl FnMut(Point, &T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.iter().map(|(p, v)| f(p, v)).collect(),
+        }
+    }
+
+    // Rows become columns
+    pub fn transpose(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid {
+            width: self.height,
+            height: self.width,
+            cells: (0..self.width)
+                .flat_map(|x| self.column(x).cloned())
+                .collect(),
+        }
+    }
+}
+
+impl Grid<char> {
+    // Grid of the characters of `text`, one row per line. Trailing empty
+    // lines are dropped; any other line must be as wide as the first.
+    pub fn parse(text: &str) -> Result<Self, RaggedLine> {
+        let lines = lines(text);
+        let width = lines.first().map_or(0, |l| l.len());
+        for (i, line) in lines.iter().enumerate() {
+            if line.len() != width {
+                return Err(RaggedLine {
+                    line: i + 1,
+                    width: line.len(),
+                    expected: width,
+                });
+            }
+        }
+        Ok(Self::from_rows(width, lines))
+    }
+
+    // Like `parse`, but short lines are padded with `fill` to the width of
+    // the longest line
+    pub fn parse_padded(text: &str, fill: char) -> Self {
+        let lines = lines(text);
+        let width = lines.iter().map(|l| l.len()).max().unwrap_or(0);
+        Self::from_rows(
+            width,
+            lines.into_iter().map(|mut line| {
+                line.resize(width, fill);
+                line
+            }),
+        )
+    }
+}
+
+fn lines(text: &str) -> Vec<Vec<char>> {
+    let mut lines: Vec<Vec<char>> = text.lines().map(|l| l.chars().collect()).collect();
+    while lines.last().is_some_and(|l| l.is_empty()) {
+        lines.pop();
+    }
+    lines
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, p: Point) -> &T {
+        self.get(p)
+            .unwrap_or_else(|| panic!("{p:?} is outside the grid"))
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, p: Point) -> &mut T {
+        self.get_mut(p)
+            .unwrap_or_else(|| panic!("{p:?} is outside the grid"))
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let grid = Grid::parse("ab\ncd\n\n").unwrap();
+        assert_eq!((grid.width(), grid.height()), (2, 2));
+        assert_eq!(grid[Point::new(1, 0)], 'b');
+        assert_eq!(grid.get(Point::new(2, 0)), None);
+        assert_eq!(grid.get(Point::new(0, -1)), None);
+        assert_eq!(grid.to_string(), "ab\ncd\n");
+
+        assert_eq!(
+            Grid::parse("abc\nd\nef"),
+            Err(RaggedLine {
+                line: 2,
+                width: 1,
+                expected: 3
+            })
+        );
+        let grid = Grid::parse_padded("abc\nd\nef", '.');
+        assert_eq!(grid.to_string(), "abc\nd..\nef.\n");
+
+        let empty = Grid::parse("").unwrap();
+        assert_eq!((empty.width(), empty.height()), (0, 0));
+        assert_eq!(empty.rows().count(), 0);
+    }
+
+    #[test]
+    fn test_access() {
+        let mut grid = Grid::parse("abc\ndef").unwrap();
+        assert_eq!(grid.row(1), ['d', 'e', 'f']);
+        assert_eq!(grid.column(2).collect::<String>(), "cf");
+        assert_eq!(grid.find(&'e'), Some(Point::new(1, 1)));
+        assert_eq!(grid.find(&'z'), None);
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf\n");
+
+        grid[Point::new(0, 0)] = 'A';
+        let upper = grid.map(|_, c| c.to_ascii_uppercase());
+        assert_eq!(upper.to_string(), "ABC\nDEF\n");
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = Grid::new(3, 3, 0);
+        assert_eq!(grid.neighbours4(Point::new(1, 1)).count(), 4);
+        assert_eq!(grid.neighbours8(Point::new(1, 1)).count(), 8);
+        assert_eq!(
+            grid.neighbours4(Point::new(0, 0)).collect::<Vec<_>>(),
+            [Point::new(1, 0), Point::new(0, 1)]
+        );
+        assert_eq!(grid.neighbours8(Point::new(2, 2)).count(), 3);
+    }
+}
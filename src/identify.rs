@@ -0,0 +1,207 @@
+use crate::input::Input;
+
+// How well an input fits the format of one day, from 0 to 1
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Guess {
+    pub day: u32,
+    pub confidence: f64,
+}
+
+fn is_number(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit())
+}
+
+fn is_integer(s: &str) -> bool {
+    is_number(s.strip_prefix('-').unwrap_or(s))
+}
+
+// `a-b` with both ends plain numbers
+fn is_range(s: &str) -> bool {
+    s.split_once('-')
+        .is_some_and(|(a, b)| is_number(a) && is_number(b))
+}
+
+fn is_tuple(line: &str, len: usize) -> bool {
+    let fields: Vec<_> = line.split(',').collect();
+    fields.len() == len && fields.iter().all(|f| is_integer(f))
+}
+
+fn is_rotation(line: &str) -> bool {
+    line.strip_prefix(['L', 'R']).is_some_and(is_number)
+}
+
+fn is_machine(line: &str) -> bool {
+    let lights = line
+        .strip_prefix('[')
+        .and_then(|rest| rest.split_once(']'))
+        .is_some_and(|(lights, _)| lights.chars().all(|c| c == '.' || c == '#'));
+    lights && line.contains('(') && line.ends_with('}')
+}
+
+fn is_device(line: &str) -> bool {
+    let is_name = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_alphanumeric());
+    line.split_once(": ")
+        .is_some_and(|(name, outputs)| is_name(name) && outputs.split(' ').all(is_name))
+}
+
+fn only(line: &str, cells: &str) -> bool {
+    line.chars().all(|c| cells.contains(c))
+}
+
+// Share of the lines for which `matches` holds
+fn share<'a>(lines: impl IntoIterator<Item = &'a str>, matches: impl Fn(&str) -> bool) -> f64 {
+    let (mut hits, mut total) = (0, 0);
+    for line in lines {
+        total += 1;
+        hits += matches(line) as usize;
+    }
+    if total == 0 {
+        0.0
+    } else {
+        hits as f64 / total as f64
+    }
+}
+
+// Days with a known input format
+const DAYS: u32 = 11;
+
+// Score the input against the format of every day
+fn scores(input: &Input) -> [f64; DAYS as usize] {
+    let lines: Vec<&str> = input.non_empty_lines().collect();
+    let lines = || lines.iter().copied();
+    let sections: Vec<&str> = input.sections().collect();
+    let text = input.text();
+
+    // Ranges separated by commas, in a single section
+    let day2 = share(lines(), |l| l.split(',').all(is_range)) / sections.len().max(1) as f64;
+    // Ranges, a blank line, then one ID per line
+    let day5 = match sections[..] {
+        [ranges, ids] => (share(ranges.lines(), is_range) + share(ids.lines(), is_number)) / 2.0,
+        _ => 0.0,
+    };
+    // Rows of numbers with a row of operators at the bottom
+    let day6 = match lines().collect::<Vec<_>>().split_last() {
+        Some((ops, rows)) if !rows.is_empty() => {
+            let numbers = share(rows.iter().copied(), |l| {
+                only(l, "0123456789 ") && l.bytes().any(|b| b.is_ascii_digit())
+            });
+            let operators = only(ops, "+* ") && ops.contains(['+', '*']);
+            (numbers + operators as u8 as f64) / 2.0
+        }
+        _ => 0.0,
+    };
+    // A rolls grid has any number of `@`, a manifold exactly one `S`
+    let rolls = text.contains('@') as u8 as f64;
+    let starts = if text.matches('S').count() == 1 {
+        1.0
+    } else {
+        0.5
+    };
+
+    [
+        share(lines(), is_rotation),
+        day2,
+        share(lines(), is_number),
+        share(lines(), |l| only(l, "@.")) * rolls,
+        day5,
+        day6,
+        share(lines(), |l| only(l, ".^S/\\#+")) * starts,
+        share(lines(), |l| is_tuple(l, 3)),
+        share(lines(), |l| is_tuple(l, 2)),
+        share(lines(), is_machine),
+        share(lines(), is_device),
+    ]
+}
+
+// Every day, most likely first
+pub fn identify(input: &Input) -> Vec<Guess> {
+    let mut guesses: Vec<Guess> = scores(input)
+        .into_iter()
+        .zip(1..)
+        .map(|(confidence, day)| Guess { day, confidence })
+        .collect();
+    guesses.sort_by(|a, b| {
+        b.confidence
+            .total_cmp(&a.confidence)
+            .then(a.day.cmp(&b.day))
+    });
+    guesses
+}
+
+pub fn has_fingerprint(day: u32) -> bool {
+    (1..=DAYS).contains(&day)
+}
+
+// The day the input fits better than `day`, if any. A day without a
+// fingerprint scores 0 against everything, so it is never second-guessed.
+pub fn mismatch(input: &Input, day: u32) -> Option<Guess> {
+    if !has_fingerprint(day) {
+        return None;
+    }
+    let best = identify(input)[0];
+    (best.day != day && best.confidence > confidence(input, day)).then_some(best)
+}
+
+// How well the input fits the format of `day`, 0 for days without one
+pub fn confidence(input: &Input, day: u32) -> f64 {
+    let scores = scores(input);
+    (day as usize)
+        .checked_sub(1)
+        .and_then(|i| scores.get(i))
+        .copied()
+        .unwrap_or(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLES: [&str; 11] = [
+        "L68\nL30\nR48\nL5\nR60",
+        "11-22,95-115,998-1012,1188511880-1188511890",
+        "987654321111111\n811111111111119\n234234234234278",
+        "..@@.@@@@.\n@@@.@.@.@@\n@@@@@.@.@@",
+        "3-5\n10-14\n16-20\n\n1\n5\n8",
+        "123 328  51 64 \n 45 64  387 23 \n  6 98  215 314\n*   +   *   +  ",
+        "..S..\n.....\n..^..\n.....",
+        "162,817,812\n57,618,57\n906,360,560",
+        "7,1\n11,1\n11,7\n9,7",
+        "[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}\n[...#.] (0,2,3,4) (2,3) {7,5,12,7,2}",
+        "aaa: you hhh\nyou: bbb ccc\nbbb: ddd eee",
+    ];
+
+    #[test]
+    fn test_examples() {
+        for (day, example) in (1..).zip(EXAMPLES) {
+            let input = Input::new(example);
+            let guesses = identify(&input);
+            assert_eq!(guesses[0].day, day, "{example}");
+            assert_eq!(guesses[0].confidence, 1.0, "{example}");
+            assert!(guesses[1].confidence < 1.0, "{example}");
+            assert_eq!(confidence(&input, day), 1.0);
+        }
+    }
+
+    #[test]
+    fn test_mixed() {
+        // One stray line lowers the confidence without changing the guess
+        let input = Input::new("L68\nL30\nhello\nR48");
+        let best = identify(&input)[0];
+        assert_eq!((best.day, best.confidence), (1, 0.75));
+
+        assert_eq!(identify(&Input::new(""))[0].confidence, 0.0);
+        assert_eq!(confidence(&Input::new("L1"), 0), 0.0);
+        assert_eq!(confidence(&Input::new("L1"), 12), 0.0);
+    }
+
+    #[test]
+    fn test_mismatch() {
+        let numbers = Input::new("987654321111111\n811111111111119");
+        assert_eq!(mismatch(&numbers, 3), None);
+        assert_eq!(mismatch(&numbers, 1).map(|g| g.day), Some(3));
+        // Days beyond the known ones have no format to compare with
+        assert!(!has_fingerprint(12));
+        assert_eq!(mismatch(&numbers, 12), None);
+        assert_eq!(mismatch(&numbers, 0), None);
+    }
+}
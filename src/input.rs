@@ -0,0 +1,105 @@
+use crate::identify;
+use std::fs;
+use std::io::{self, BufRead, Cursor};
+use std::path::Path;
+use std::sync::Once;
+
+// Puzzle input with the quirks of how it was saved smoothed out: a leading
+// byte order mark is dropped, CRLF line endings become LF, trailing
+// whitespace is trimmed from every line and trailing blank lines are removed.
+// Blank lines inside the input are kept, since they separate sections.
+pub struct Input {
+    text: String,
+}
+
+impl Input {
+    pub fn new(raw: &str) -> Self {
+        let raw = raw.strip_prefix('\u{feff}').unwrap_or(raw);
+        let mut lines: Vec<&str> = raw.lines().map(str::trim_end).collect();
+        while lines.last().is_some_and(|l| l.is_empty()) {
+            lines.pop();
+        }
+
+        let mut text = lines.join("\n");
+        if !text.is_empty() {
+            text.push('\n');
+        }
+        Self { text }
+    }
+
+    pub fn from_reader<R: BufRead>(reader: R) -> Self {
+        Self::new(&io::read_to_string(reader).expect("Failed to read input"))
+    }
+
+    // The normalized text, each line ending in a newline
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    // Every line, blank ones included, so that positions match the file
+    pub fn lines(&self) -> impl Iterator<Item = &str> {
+        self.text.lines()
+    }
+
+    // Lines with something on them
+    pub fn non_empty_lines(&self) -> impl Iterator<Item = &str> {
+        self.lines().filter(|l| !l.is_empty())
+    }
+
+    // Groups of lines separated by one or more blank lines
+    pub fn sections(&self) -> impl Iterator<Item = &str> {
+        self.text
+            .split("\n\n")
+            .map(|s| s.trim_matches('\n'))
+            .filter(|s| !s.is_empty())
+    }
+}
+
+// The input of `day`, from the `inputs` directory at the workspace root. The
+// first time, a warning is printed if it looks more like another day's input,
+// for days whose input format is known.
+pub fn read_input(day: u32) -> Cursor<String> {
+    static CHECK: Once = Once::new();
+
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(format!("inputs/day{day:02}.txt"));
+    let text = fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("Failed to open {}: {e}", path.display()));
+
+    CHECK.call_once(|| {
+        if let Some(best) = identify::mismatch(&Input::new(&text), day) {
+            eprintln!(
+                "Warning: {} looks like input for day {} ({:.0}% confidence), not day {day}",
+                path.display(),
+                best.day,
+                best.confidence * 100.0
+            );
+        }
+    });
+
+    Cursor::new(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        let input = Input::new("\u{feff}L68 \r\nR30\t\r\n\r\n\r\n");
+        assert_eq!(input.text(), "L68\nR30\n");
+
+        let input = Input::new("\n1-3\n\n5\n  \n");
+        assert_eq!(input.lines().collect::<Vec<_>>(), ["", "1-3", "", "5"]);
+        assert_eq!(input.non_empty_lines().count(), 2);
+
+        assert_eq!(Input::new("").text(), "");
+        assert_eq!(Input::new(" \r\n\n").lines().count(), 0);
+    }
+
+    #[test]
+    fn test_sections() {
+        let input = Input::new("\r\n3-5\r\n10-14\r\n\r\n\r\n1\r\n5\r\n\r\n");
+        assert_eq!(input.sections().collect::<Vec<_>>(), ["3-5\n10-14", "1\n5"]);
+        assert_eq!(Input::new("").sections().count(), 0);
+    }
+}
@@ -0,0 +1,11 @@
+pub mod args;
+pub mod diff;
+pub mod geometry;
+pub mod graph;
+pub mod grid;
+pub mod identify;
+pub mod input;
+pub mod rng;
+pub mod scaffold;
+pub mod solution;
+pub mod union_find;
@@ -0,0 +1,80 @@
+use aoc::identify::identify;
+use aoc::input::Input;
+use aoc::scaffold;
+use std::fs;
+use std::path::Path;
+use std::process::{self, Command};
+
+fn usage() -> ! {
+    eprintln!("Usage: aoc identify <file>");
+    eprintln!("       aoc new <day>");
+    eprintln!("       aoc run <day> [args...]");
+    process::exit(2);
+}
+
+fn fail(message: impl std::fmt::Display) -> ! {
+    eprintln!("{message}");
+    process::exit(1);
+}
+
+fn parse_day(day: &str) -> u32 {
+    day.parse()
+        .unwrap_or_else(|_| fail(format!("Invalid day {day:?}")))
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+
+    match args[..] {
+        ["identify", path] => {
+            let text = fs::read_to_string(path)
+                .unwrap_or_else(|e| fail(format!("Failed to read {path}: {e}")));
+            let guesses = identify(&Input::new(&text));
+            let candidates: Vec<_> = guesses.iter().filter(|g| g.confidence > 0.0).collect();
+
+            match candidates.split_first() {
+                Some((best, others)) => {
+                    println!(
+                        "day{:02} ({:.0}% confidence)",
+                        best.day,
+                        best.confidence * 100.0
+                    );
+                    for guess in others.iter().take(2) {
+                        println!(
+                            "  or day{:02} ({:.0}%)",
+                            guess.day,
+                            guess.confidence * 100.0
+                        );
+                    }
+                }
+                None => fail(format!("{path} does not look like any day's input")),
+            }
+        }
+        ["new", day] => match scaffold::new_day(root, parse_day(day)) {
+            Ok(dir) => println!("Created {}", dir.display()),
+            Err(e) => fail(e),
+        },
+        ["run", day, ref rest @ ..] => {
+            // Every workspace member is a day the runner knows about
+            let name = format!("day{:02}", parse_day(day));
+            let manifest = fs::read_to_string(root.join("Cargo.toml"))
+                .unwrap_or_else(|e| fail(format!("Failed to read Cargo.toml: {e}")));
+            if !manifest.contains(&format!("\"{name}\"")) {
+                fail(format!(
+                    "{name} is not in the workspace, add it with `aoc new {day}`"
+                ));
+            }
+            let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+            let status = Command::new(cargo)
+                .current_dir(root)
+                .args(["run", "--release", "-p", &name, "--"])
+                .args(rest)
+                .status()
+                .unwrap_or_else(|e| fail(format!("Failed to run cargo: {e}")));
+            process::exit(status.code().unwrap_or(1));
+        }
+        _ => usage(),
+    }
+}
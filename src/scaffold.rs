@@ -0,0 +1,185 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const CARGO_TOML: &str = r#"[package]
+name = "{name}"
+version = "0.1.0"
+edition = "2024"
+
+[dependencies]
+aoc = { path = ".." }
+"#;
+
+const MAIN_RS: &str = r#"use aoc::input::Input;
+use aoc::solution::{self, Solution};
+use std::fmt::Display;
+
+struct {type};
+
+impl Solution for {type} {
+    const DAY: u32 = {day};
+
+    // Placeholder until the puzzle is solved
+    fn part1(input: &Input) -> impl Display {
+        input.non_empty_lines().count()
+    }
+
+    fn part2(input: &Input) -> impl Display {
+        input.sections().count()
+    }
+}
+
+fn main() {
+    solution::run::<{type}>();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_example() {
+        solution::check_example::<{type}>(
+            include_str!("../fixtures/example.txt"),
+            include_str!("../fixtures/answers.txt"),
+        );
+    }
+}
+"#;
+
+const ANSWERS: &str = "part1:\npart2:\n";
+
+fn fill(template: &str, day: u32) -> String {
+    template
+        .replace("{name}", &format!("day{day:02}"))
+        .replace("{type}", &format!("Day{day:02}"))
+        .replace("{day}", &day.to_string())
+}
+
+// `manifest` with `name` added to its workspace members, which are kept sorted
+pub fn add_member(manifest: &str, name: &str) -> io::Result<String> {
+    let invalid = |message| io::Error::new(io::ErrorKind::InvalidData, message);
+    let key = manifest
+        .find("members")
+        .ok_or_else(|| invalid("No workspace members in Cargo.toml"))?;
+    let open = key
+        + manifest[key..]
+            .find('[')
+            .ok_or_else(|| invalid("Workspace members are not a list"))?;
+    let close = open
+        + manifest[open..]
+            .find(']')
+            .ok_or_else(|| invalid("Unterminated workspace members list"))?;
+
+    let mut members: Vec<String> = manifest[open + 1..close]
+        .split(',')
+        .map(|m| m.trim().trim_matches('"').to_string())
+        .filter(|m| !m.is_empty())
+        .collect();
+    if members.iter().any(|m| m == name) {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{name} is already a workspace member"),
+        ));
+    }
+    members.push(name.to_string());
+    members.sort();
+
+    let list: Vec<_> = members.iter().map(|m| format!("\"{m}\"")).collect();
+    Ok(format!(
+        "{}[{}]{}",
+        &manifest[..open],
+        list.join(", "),
+        &manifest[close + 1..]
+    ))
+}
+
+// Create the crate for `day` in the workspace at `root`: a solution template,
+// empty example and answer fixtures, and its workspace entry. Returns the
+// crate's directory.
+pub fn new_day(root: &Path, day: u32) -> io::Result<PathBuf> {
+    if !(1..=25).contains(&day) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("No day {day}, expected 1 to 25"),
+        ));
+    }
+    let name = format!("day{day:02}");
+    let dir = root.join(&name);
+    if dir.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists", dir.display()),
+        ));
+    }
+
+    // Check the manifest before creating anything, so a bad one leaves no
+    // half-made crate behind
+    let manifest_path = root.join("Cargo.toml");
+    let manifest = add_member(&fs::read_to_string(&manifest_path)?, &name)?;
+
+    fs::create_dir_all(dir.join("src"))?;
+    fs::create_dir_all(dir.join("fixtures"))?;
+    fs::write(dir.join("Cargo.toml"), fill(CARGO_TOML, day))?;
+    fs::write(dir.join("src/main.rs"), fill(MAIN_RS, day))?;
+    fs::write(dir.join("fixtures/example.txt"), "")?;
+    fs::write(dir.join("fixtures/answers.txt"), ANSWERS)?;
+    fs::write(&manifest_path, manifest)?;
+
+    Ok(dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = "[package]\nname = \"aoc\"\n\n[workspace]\n\
+                            members = [\"day01\", \"day02\", \"day10\"]\nresolver = \"2\"\n";
+
+    #[test]
+    fn test_add_member() {
+        let manifest = add_member(MANIFEST, "day03").unwrap();
+        assert!(manifest.contains("members = [\"day01\", \"day02\", \"day03\", \"day10\"]\n"));
+        assert!(manifest.ends_with("resolver = \"2\"\n"));
+
+        let multiline = "[workspace]\nmembers = [\n    \"day01\",\n]\n";
+        assert_eq!(
+            add_member(multiline, "day02").unwrap(),
+            "[workspace]\nmembers = [\"day01\", \"day02\"]\n"
+        );
+
+        let err = add_member(MANIFEST, "day02").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+        assert!(add_member("[package]\n", "day02").is_err());
+    }
+
+    #[test]
+    fn test_new_day() {
+        let root = std::env::temp_dir().join(format!("aoc-scaffold-{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("Cargo.toml"), MANIFEST).unwrap();
+
+        let dir = new_day(&root, 12).unwrap();
+        assert_eq!(dir, root.join("day12"));
+        let main = fs::read_to_string(dir.join("src/main.rs")).unwrap();
+        assert!(main.contains("impl Solution for Day12 {\n    const DAY: u32 = 12;"));
+        let cargo = fs::read_to_string(dir.join("Cargo.toml")).unwrap();
+        assert!(cargo.contains("name = \"day12\"\nversion = \"0.1.0\"\nedition = \"2024\""));
+        assert_eq!(
+            fs::read_to_string(dir.join("fixtures/answers.txt")).unwrap(),
+            "part1:\npart2:\n"
+        );
+        let manifest = fs::read_to_string(root.join("Cargo.toml")).unwrap();
+        assert!(manifest.contains("\"day10\", \"day12\"]"));
+
+        let err = new_day(&root, 12).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+        assert_eq!(
+            new_day(&root, 26).unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,69 @@
+use crate::input::{Input, read_input};
+use std::fmt::Display;
+
+// A day's puzzle: both parts solved from the normalized input
+pub trait Solution {
+    const DAY: u32;
+
+    fn part1(input: &Input) -> impl Display;
+    fn part2(input: &Input) -> impl Display;
+}
+
+// Solve both parts of the day's real input and print the answers
+pub fn run<S: Solution>() {
+    let input = Input::from_reader(read_input(S::DAY));
+    println!("Part 1: {}", S::part1(&input));
+    println!("Part 2: {}", S::part2(&input));
+}
+
+// Check the answers to an example, given as `part1: <answer>` and
+// `part2: <answer>` lines. Parts whose answer is still blank are skipped.
+pub fn check_example<S: Solution>(example: &str, answers: &str) {
+    let input = Input::new(example);
+    for line in Input::new(answers).non_empty_lines() {
+        let (part, expected) = line
+            .split_once(':')
+            .unwrap_or_else(|| panic!("Expected `part<n>: <answer>`, got {line:?}"));
+        let expected = expected.trim();
+        if expected.is_empty() {
+            continue;
+        }
+        let answer = match part {
+            "part1" => S::part1(&input).to_string(),
+            "part2" => S::part2(&input).to_string(),
+            _ => panic!("Unknown part {part:?}"),
+        };
+        assert_eq!(answer, expected, "day {} {part}", S::DAY);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Lines;
+
+    impl Solution for Lines {
+        const DAY: u32 = 0;
+
+        fn part1(input: &Input) -> impl Display {
+            input.non_empty_lines().count()
+        }
+
+        fn part2(input: &Input) -> impl Display {
+            input.sections().count()
+        }
+    }
+
+    #[test]
+    fn test_check_example() {
+        check_example::<Lines>("a\nb\n\nc\n", "part1: 3\npart2: 2\n");
+        check_example::<Lines>("a\n", "part1: 1\npart2:\n");
+    }
+
+    #[test]
+    #[should_panic(expected = "day 0 part2")]
+    fn test_wrong_answer() {
+        check_example::<Lines>("a\nb\n\nc\n", "part1: 3\npart2: 3\n");
+    }
+}
@@ -0,0 +1,149 @@
+use std::collections::BTreeMap;
+
+// Disjoint-set forest with union by rank and path compression. The number of
+// components and a histogram of their sizes are kept up to date on every
+// union, so none of the queries below need to scan all elements.
+pub struct UnionFind {
+    parent: Vec<usize>,
+    rank: Vec<u8>,
+    size: Vec<usize>,
+    components: usize,
+    // Component size -> number of components of that size
+    histogram: BTreeMap<usize, usize>,
+}
+
+impl UnionFind {
+    pub fn new(n: usize) -> Self {
+        Self {
+            parent: (0..n).collect(),
+            rank: vec![0; n],
+            size: vec![1; n],
+            components: n,
+            histogram: if n > 0 {
+                BTreeMap::from([(1, n)])
+            } else {
+                BTreeMap::new()
+            },
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    // Iterative, so long parent chains cannot overflow the stack
+    pub fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+
+        let mut x = x;
+        while self.parent[x] != root {
+            let next = self.parent[x];
+            self.parent[x] = root;
+            x = next;
+        }
+
+        root
+    }
+
+    // Merge the components of `x` and `y`; false if they were already one
+    pub fn union(&mut self, x: usize, y: usize) -> bool {
+        let (px, py) = (self.find(x), self.find(y));
+        if px == py {
+            return false;
+        }
+
+        let (child, root) = if self.rank[px] < self.rank[py] {
+            (px, py)
+        } else {
+            (py, px)
+        };
+        if self.rank[child] == self.rank[root] {
+            self.rank[root] += 1;
+        }
+
+        let (a, b) = (self.size[child], self.size[root]);
+        self.forget_size(a);
+        self.forget_size(b);
+        *self.histogram.entry(a + b).or_insert(0) += 1;
+
+        self.parent[child] = root;
+        self.size[root] = a + b;
+        self.components -= 1;
+        true
+    }
+
+    fn forget_size(&mut self, size: usize) {
+        let count = self.histogram.get_mut(&size).unwrap();
+        *count -= 1;
+        if *count == 0 {
+            self.histogram.remove(&size);
+        }
+    }
+
+    pub fn components(&self) -> usize {
+        self.components
+    }
+
+    pub fn size_of(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.size[root]
+    }
+
+    // Sizes of the `k` largest components, largest first
+    pub fn largest_k(&self, k: usize) -> Vec<usize> {
+        self.histogram
+            .iter()
+            .rev()
+            .flat_map(|(&size, &count)| std::iter::repeat_n(size, count))
+            .take(k)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_union_find() {
+        let mut uf = UnionFind::new(6);
+        assert_eq!(uf.components(), 6);
+        assert_eq!(uf.largest_k(2), vec![1, 1]);
+
+        assert!(uf.union(0, 1));
+        assert!(uf.union(1, 2));
+        assert!(!uf.union(0, 2));
+        assert!(uf.union(3, 4));
+
+        assert_eq!(uf.components(), 3);
+        assert_eq!(uf.size_of(2), 3);
+        assert_eq!(uf.size_of(4), 2);
+        assert_eq!(uf.find(0), uf.find(2));
+        assert_ne!(uf.find(0), uf.find(3));
+        assert_eq!(uf.largest_k(2), vec![3, 2]);
+        assert_eq!(uf.largest_k(10), vec![3, 2, 1]);
+
+        assert!(uf.union(5, 0));
+        assert!(uf.union(4, 5));
+        assert_eq!(uf.components(), 1);
+        assert_eq!(uf.largest_k(3), vec![6]);
+    }
+
+    #[test]
+    fn test_long_chain() {
+        let n = 1_000_000;
+        let mut uf = UnionFind::new(n);
+        for i in 1..n {
+            uf.union(i - 1, i);
+        }
+        assert_eq!(uf.components(), 1);
+        assert_eq!(uf.size_of(n - 1), n);
+    }
+}